@@ -0,0 +1,137 @@
+//! A/B comparison of two BMB compiler binaries.
+//!
+//! Every selected benchmark is built natively with both compilers and the
+//! two executables are run interleaved (round-robin or shuffled, see
+//! `schedule`) so thermal drift and background load hit both sides equally.
+
+use crate::{schedule, stats};
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+
+/// Per-benchmark outcome of an A/B run.
+struct AbResult {
+    name: String,
+    a_times: Vec<f64>,
+    b_times: Vec<f64>,
+}
+
+pub fn compare_compilers(
+    benchmarks: &[(String, PathBuf)],
+    bmb_a: &Path,
+    bmb_b: &Path,
+    iterations: u32,
+    warmup: u32,
+    confidence: f64,
+) {
+    println!("{}", "=== BMB Compiler A/B Comparison ===".cyan().bold());
+    println!("A: {}", bmb_a.display());
+    println!("B: {}", bmb_b.display());
+    println!();
+
+    println!(
+        "{:<20} {:>10} {:>10} {:>8} {:>16} {:>10}",
+        "Benchmark", "A (ms)", "B (ms)", "B/A", "CI", "Verdict"
+    );
+    println!("{}", "-".repeat(80));
+
+    let mut results = Vec::new();
+    let mut skipped = Vec::new();
+
+    for (name, path) in benchmarks {
+        let bmb_dir = path.join("bmb");
+        let (Some(exe_a), Some(exe_b)) = (
+            crate::compile_bmb_native(&bmb_dir, bmb_a, "main", "main_a"),
+            crate::compile_bmb_native(&bmb_dir, bmb_b, "main", "main_b"),
+        ) else {
+            skipped.push(name.clone());
+            continue;
+        };
+
+        let (a_times, b_times) = run_interleaved(&exe_a, &exe_b, iterations, warmup);
+        if a_times.is_empty() || b_times.is_empty() {
+            skipped.push(name.clone());
+            continue;
+        }
+
+        let result = AbResult {
+            name: name.clone(),
+            a_times,
+            b_times,
+        };
+        print_row(&result, confidence);
+        results.push(result);
+    }
+
+    println!("{}", "-".repeat(80));
+
+    if !skipped.is_empty() {
+        println!("{} not built and run by both compilers: {}", "Skipped".yellow(), skipped.join(", "));
+    }
+
+    if results.is_empty() {
+        println!("{}", "No benchmarks could be compared.".yellow());
+        return;
+    }
+
+    let ratios: Vec<f64> = results
+        .iter()
+        .map(|r| stats::median(&r.b_times) / stats::median(&r.a_times))
+        .collect();
+    let geomean = stats::geomean(&ratios);
+    let (faster, slower) = results.iter().fold((0, 0), |(f, s), r| {
+        let ci = stats::ratio_ci(&r.b_times, &r.a_times, confidence);
+        match (ci.excludes(1.0), ci.estimate < 1.0) {
+            (true, true) => (f + 1, s),
+            (true, false) => (f, s + 1),
+            _ => (f, s),
+        }
+    });
+
+    println!();
+    println!(
+        "{}: geometric mean B/A = {:.3}x ({}), {} significantly faster, {} significantly slower, {} unchanged",
+        "Summary".cyan().bold(),
+        geomean,
+        describe_change(geomean),
+        faster.to_string().green(),
+        if slower > 0 { slower.to_string().red() } else { slower.to_string().green() },
+        results.len() - faster - slower
+    );
+}
+
+/// Time A and B in the configured interleaved order.
+fn run_interleaved(a: &Path, b: &Path, iterations: u32, warmup: u32) -> (Vec<f64>, Vec<f64>) {
+    let mut times = schedule::measure(&[a, b], iterations, warmup);
+    let b_times = times.pop().unwrap_or_default();
+    let a_times = times.pop().unwrap_or_default();
+    (a_times, b_times)
+}
+
+fn print_row(result: &AbResult, confidence: f64) {
+    let ci = stats::ratio_ci(&result.b_times, &result.a_times, confidence);
+    let verdict = if !ci.excludes(1.0) {
+        "~".yellow().to_string()
+    } else if ci.estimate < 1.0 {
+        "faster".green().to_string()
+    } else {
+        "slower".red().to_string()
+    };
+
+    println!(
+        "{:<20} {:>10.2} {:>10.2} {:>7.3}x {:>16} {:>10}",
+        result.name,
+        stats::median(&result.a_times),
+        stats::median(&result.b_times),
+        ci.estimate,
+        format!("[{:.3}, {:.3}]", ci.low, ci.high),
+        verdict
+    );
+}
+
+fn describe_change(ratio: f64) -> String {
+    if ratio < 1.0 {
+        format!("B {:.1}% faster", (1.0 - ratio) * 100.0)
+    } else {
+        format!("B {:.1}% slower", (ratio - 1.0) * 100.0)
+    }
+}
@@ -0,0 +1,152 @@
+//! A/A noise-floor calibration.
+//!
+//! The same executable is timed as two interleaved "implementations" in
+//! repeated trials. Every trial yields a ratio of medians that would be
+//! exactly 1.0 on a noiseless machine; the spread of those ratios is the
+//! smallest difference this machine can resolve. The result is saved per
+//! host as `results/calibration-<hostname>.json`, where `diff` and `gate`
+//! pick it up.
+
+use crate::host::HostInfo;
+use crate::{schedule, stats};
+use chrono::Local;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Percentile of |A/A - 1| reported as the noise floor.
+const FLOOR_PERCENTILE: f64 = 0.95;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Calibration {
+    pub timestamp: String,
+    pub host: HostInfo,
+    /// Implementation that was timed against itself
+    pub language: String,
+    pub iterations: u32,
+    pub trials: u32,
+    pub benchmarks: Vec<CalibrationEntry>,
+    /// 95th percentile of |ratio - 1| over all trials, as a fraction
+    pub noise_floor: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CalibrationEntry {
+    pub benchmark: String,
+    /// A'/A ratio of medians of every trial
+    pub ratios: Vec<f64>,
+}
+
+pub struct CalibrateOptions<'a> {
+    pub language: &'a str,
+    pub iterations: u32,
+    pub warmup: u32,
+    pub trials: u32,
+}
+
+/// Calibrate on the given benchmarks and save the result to `results_dir`.
+pub fn run(benchmarks: &[(String, PathBuf)], results_dir: &Path, opts: &CalibrateOptions) {
+    println!("{}", "=== BMB A/A Noise Calibration ===".cyan().bold());
+    println!(
+        "Timing {} against itself: {} trials x {} iterations per side",
+        opts.language.to_uppercase(),
+        opts.trials,
+        opts.iterations
+    );
+    println!();
+    println!(
+        "{:<20} {:>8} {:>10} {:>10} {:>10} {:>10}",
+        "Benchmark", "Trials", "Median", "P5", "P95", "Max |dev|"
+    );
+    println!("{}", "-".repeat(73));
+
+    let mut entries = Vec::new();
+    for (name, path) in benchmarks {
+        let Some(exe) = crate::build_implementation(path, opts.language) else {
+            continue;
+        };
+
+        let mut ratios = Vec::new();
+        for _ in 0..opts.trials {
+            let times = schedule::measure(&[&exe, &exe], opts.iterations, opts.warmup);
+            if times.iter().all(|t| !t.is_empty()) {
+                ratios.push(stats::median(&times[1]) / stats::median(&times[0]));
+            }
+        }
+        if ratios.is_empty() {
+            continue;
+        }
+
+        let max_dev = ratios.iter().map(|r| (r - 1.0).abs()).fold(0.0, f64::max);
+        println!(
+            "{:<20} {:>8} {:>9.3}x {:>9.3}x {:>9.3}x {:>9.1}%",
+            name,
+            ratios.len(),
+            stats::median(&ratios),
+            stats::percentile(&ratios, 0.05),
+            stats::percentile(&ratios, 0.95),
+            max_dev * 100.0
+        );
+        entries.push(CalibrationEntry {
+            benchmark: name.clone(),
+            ratios,
+        });
+    }
+
+    println!("{}", "-".repeat(73));
+    if entries.is_empty() {
+        println!("{}", "No benchmarks could be calibrated.".yellow());
+        return;
+    }
+
+    let deviations: Vec<f64> = entries
+        .iter()
+        .flat_map(|e| &e.ratios)
+        .map(|r| (r - 1.0).abs())
+        .collect();
+    let noise_floor = stats::percentile(&deviations, FLOOR_PERCENTILE);
+
+    println!();
+    println!(
+        "{}: ±{:.1}% (95th percentile of |A/A - 1| over {} trials)",
+        "Noise floor".cyan().bold(),
+        noise_floor * 100.0,
+        deviations.len()
+    );
+
+    let calibration = Calibration {
+        timestamp: Local::now().to_rfc3339(),
+        host: HostInfo::detect(),
+        language: opts.language.to_string(),
+        iterations: opts.iterations,
+        trials: opts.trials,
+        benchmarks: entries,
+        noise_floor,
+    };
+    match save(results_dir, &calibration) {
+        Ok(path) => println!("{} {}", "Saved:".green(), path.display()),
+        Err(e) => println!("{} {}", "Error:".red(), e),
+    }
+}
+
+fn calibration_path(results_dir: &Path, hostname: &str) -> PathBuf {
+    let host = if hostname.is_empty() { "local" } else { hostname };
+    results_dir.join(format!("calibration-{}.json", host))
+}
+
+fn save(results_dir: &Path, calibration: &Calibration) -> Result<PathBuf, String> {
+    let path = calibration_path(results_dir, &calibration.host.hostname);
+    let json = serde_json::to_string_pretty(calibration).map_err(|e| e.to_string())?;
+    fs::create_dir_all(results_dir)
+        .and_then(|_| fs::write(&path, json))
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// Calibration of the machine we are running on, if one was saved.
+pub fn load_for_host(results_dir: &Path) -> Option<Calibration> {
+    let path = calibration_path(results_dir, &HostInfo::detect().hostname);
+    let text = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&text).ok()
+}
@@ -0,0 +1,212 @@
+//! `list`: what each benchmark is, which implementations it has, whether
+//! their toolchains are installed and how they did in the latest stored run.
+
+use crate::history::HistoryRecord;
+use crate::manifest::{self, Manifest};
+use crate::report;
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Formats accepted by `list --format`.
+pub const FORMATS: [&str; 2] = ["text", "json"];
+
+/// Implementation every ratio is taken against.
+const BASELINE: &str = "c";
+
+/// Compiler a language directory needs.
+fn toolchain(lang: &str) -> &'static str {
+    match lang {
+        "c" => "gcc",
+        "rust" => "rustc",
+        _ => "bmb",
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Toolchain {
+    pub name: String,
+    /// Where `which` found it; `None` if it is not on PATH
+    pub path: Option<PathBuf>,
+}
+
+/// The toolchains of every language, in C, Rust, BMB order.
+pub fn toolchains() -> Vec<Toolchain> {
+    ["c", "rust", "bmb"]
+        .iter()
+        .map(|lang| Toolchain {
+            name: toolchain(lang).to_string(),
+            path: which::which(toolchain(lang)).ok(),
+        })
+        .collect()
+}
+
+#[derive(Debug, Serialize)]
+pub struct Entry {
+    pub name: String,
+    pub category: String,
+    pub description: Option<String>,
+    pub input_size: Option<String>,
+    pub tags: Vec<String>,
+    pub implementations: Vec<Implementation>,
+    /// Malformed bench.toml
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manifest_error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Implementation {
+    /// `lang` or `lang:variant`
+    pub name: String,
+    pub toolchain: String,
+    pub available: bool,
+    pub latest: Option<Latest>,
+}
+
+/// The implementation's median in the newest history record that has it.
+#[derive(Debug, Serialize)]
+pub struct Latest {
+    pub median_ms: f64,
+    /// Median over C's median in the same record, if C was measured
+    pub ratio: Option<f64>,
+    pub timestamp: String,
+    pub session: Option<String>,
+}
+
+impl Entry {
+    pub fn new(name: &str, path: &Path, toolchains: &[Toolchain], history: &[HistoryRecord]) -> Self {
+        let category = manifest::category(path);
+        let (manifest, manifest_error) = match Manifest::load(path) {
+            Ok(manifest) => (manifest, None),
+            Err(e) => (Manifest::default(), Some(e)),
+        };
+
+        let mut specs = crate::implementations(path);
+        specs.sort_by_key(|spec| (report::language_rank(spec), spec.clone()));
+
+        let implementations = specs
+            .into_iter()
+            .map(|spec| {
+                let (lang, _) = crate::implementation_source(&spec);
+                let tool = toolchain(lang);
+                Implementation {
+                    available: toolchains.iter().any(|t| t.name == tool && t.path.is_some()),
+                    toolchain: tool.to_string(),
+                    latest: latest(history, name, &category, &spec),
+                    name: spec,
+                }
+            })
+            .collect();
+
+        Entry {
+            name: name.to_string(),
+            tags: manifest::tags(path),
+            category,
+            description: manifest.description,
+            input_size: manifest.input_size,
+            implementations,
+            manifest_error,
+        }
+    }
+}
+
+fn latest(history: &[HistoryRecord], benchmark: &str, category: &str, spec: &str) -> Option<Latest> {
+    let median = |record: &HistoryRecord, language: &str| {
+        record
+            .samples
+            .iter()
+            .find(|s| s.benchmark == benchmark && s.category == category && s.language == language)
+            .map(|s| s.median_ms)
+    };
+
+    // History is ordered oldest first
+    history.iter().rev().find_map(|record| {
+        let median_ms = median(record, spec)?;
+        Some(Latest {
+            median_ms,
+            ratio: median(record, BASELINE).map(|base| median_ms / base),
+            timestamp: record.timestamp.clone(),
+            session: record.session.clone(),
+        })
+    })
+}
+
+pub fn render_json(toolchains: &[Toolchain], entries: &[Entry]) -> String {
+    #[derive(Serialize)]
+    struct Listing<'a> {
+        toolchains: &'a [Toolchain],
+        benchmarks: &'a [Entry],
+    }
+    serde_json::to_string_pretty(&Listing {
+        toolchains,
+        benchmarks: entries,
+    })
+    .unwrap_or_default()
+}
+
+pub fn print_text(toolchains: &[Toolchain], entries: &[Entry]) {
+    println!("{}", "Available Benchmarks".cyan().bold());
+    println!();
+
+    let tools: Vec<String> = toolchains
+        .iter()
+        .map(|t| match &t.path {
+            Some(path) => format!("{} {} ({})", "✓".green(), t.name, path.display()),
+            None => format!("{} {} (not on PATH)", "✗".red(), t.name),
+        })
+        .collect();
+    println!("Toolchains: {}", tools.join(", "));
+
+    let mut by_category: BTreeMap<&str, Vec<&Entry>> = BTreeMap::new();
+    for entry in entries {
+        by_category.entry(&entry.category).or_default().push(entry);
+    }
+
+    for (category, entries) in by_category {
+        println!("\n{}:", category.to_uppercase().green());
+        for entry in entries {
+            match &entry.description {
+                Some(description) => println!("  {} - {}", entry.name.bold(), description),
+                None => println!("  {}", entry.name.bold()),
+            }
+
+            let mut facts = Vec::new();
+            if let Some(input) = &entry.input_size {
+                facts.push(format!("input: {}", input));
+            }
+            let tags: Vec<&str> = entry.tags.iter().map(String::as_str).filter(|t| *t != entry.category).collect();
+            if !tags.is_empty() {
+                facts.push(format!("tags: {}", tags.join(", ")));
+            }
+            if !facts.is_empty() {
+                println!("    {}", facts.join("   ").dimmed());
+            }
+            if let Some(error) = &entry.manifest_error {
+                println!("    {} {}", "Warning:".yellow(), error);
+            }
+
+            for implementation in &entry.implementations {
+                let status = if implementation.available {
+                    "✓".green()
+                } else {
+                    "✗".red()
+                };
+                let latest = match &implementation.latest {
+                    Some(latest) => {
+                        let ratio = latest.ratio.map(|r| format!("{:.2}x", r)).unwrap_or_else(|| "-".to_string());
+                        let date = latest.timestamp.get(..10).unwrap_or(&latest.timestamp);
+                        format!("{:>10.2} ms {:>7}   {}", latest.median_ms, ratio, date)
+                    }
+                    None => format!("{:>13} {:>7}", "-", "-"),
+                };
+                let missing = if implementation.available {
+                    String::new()
+                } else {
+                    format!("   ({} not installed)", implementation.toolchain)
+                };
+                println!("    {} {:<12} {}{}", status, implementation.name.to_uppercase(), latest, missing);
+            }
+        }
+    }
+}
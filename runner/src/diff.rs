@@ -0,0 +1,134 @@
+//! Diffing of two saved result files.
+//!
+//! Benchmarks are matched by (benchmark, language). A change is reported
+//! as a regression or improvement only when it exceeds the threshold and,
+//! where both sides have enough samples, the bootstrap confidence interval
+//! of the new/old ratio excludes 1.0.
+
+use crate::results::load_reports;
+use crate::{stats, BenchmarkResult, ComparisonReport};
+use colored::Colorize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Change {
+    Regression,
+    Improvement,
+    Noise,
+}
+
+pub struct DiffOptions<'a> {
+    /// Minimum relative change that counts, e.g. 0.05 for 5%
+    pub threshold: f64,
+    pub confidence: f64,
+    pub language: Option<&'a str>,
+}
+
+/// Compare two result files. Returns the number of regressions.
+pub fn diff_files(old_path: &Path, new_path: &Path, opts: &DiffOptions) -> Result<usize, String> {
+    let old = index(load_reports(old_path)?, opts.language);
+    let new = index(load_reports(new_path)?, opts.language);
+
+    println!("{}", "=== BMB Result Diff ===".cyan().bold());
+    println!("Old: {}", old_path.display());
+    println!("New: {}", new_path.display());
+    println!(
+        "Threshold: {:.1}%, confidence {:.0}%",
+        opts.threshold * 100.0,
+        opts.confidence * 100.0
+    );
+    println!();
+    println!(
+        "{:<20} {:<6} {:>12} {:>12} {:>9} {:>18} {:>12}",
+        "Benchmark", "Lang", "Old (ms)", "New (ms)", "Change", "CI", "Status"
+    );
+    println!("{}", "-".repeat(95));
+
+    let mut regressions = 0;
+    let mut improvements = 0;
+
+    for (key, new_result) in &new {
+        let Some(old_result) = old.get(key) else {
+            continue;
+        };
+
+        let (change, ci) = classify(old_result, new_result, opts);
+        let status = match change {
+            Change::Regression => {
+                regressions += 1;
+                "REGRESSION".red().bold().to_string()
+            }
+            Change::Improvement => {
+                improvements += 1;
+                "IMPROVED".green().bold().to_string()
+            }
+            Change::Noise => "~".to_string(),
+        };
+
+        println!(
+            "{:<20} {:<6} {:>12.2} {:>12.2} {:>+8.1}% {:>18} {:>12}",
+            key.0,
+            key.1.to_uppercase(),
+            old_result.median_ms,
+            new_result.median_ms,
+            (ci.estimate - 1.0) * 100.0,
+            format!("[{:.3}, {:.3}]", ci.low, ci.high),
+            status
+        );
+    }
+
+    println!("{}", "-".repeat(95));
+
+    let added: Vec<_> = new.keys().filter(|k| !old.contains_key(*k)).collect();
+    let removed: Vec<_> = old.keys().filter(|k| !new.contains_key(*k)).collect();
+    for (label, keys) in [("Added", added), ("Removed", removed)] {
+        if !keys.is_empty() {
+            let names: Vec<_> = keys.iter().map(|(b, l)| format!("{}/{}", b, l)).collect();
+            println!("{}: {}", label.yellow(), names.join(", "));
+        }
+    }
+
+    println!();
+    println!(
+        "{}: {} regressions, {} improvements",
+        "Summary".cyan().bold(),
+        if regressions > 0 { regressions.to_string().red() } else { regressions.to_string().green() },
+        improvements.to_string().green()
+    );
+
+    Ok(regressions)
+}
+
+/// Classify the change from `old` to `new` and return the new/old ratio.
+fn classify(old: &BenchmarkResult, new: &BenchmarkResult, opts: &DiffOptions) -> (Change, stats::Interval) {
+    let ci = if old.times_ms.len() >= 2 && new.times_ms.len() >= 2 {
+        stats::ratio_ci(&new.times_ms, &old.times_ms, opts.confidence)
+    } else {
+        // Single-sample or summary-only results: no interval available
+        let ratio = new.median_ms / old.median_ms;
+        stats::Interval { estimate: ratio, low: ratio, high: ratio }
+    };
+
+    let change = if ci.estimate > 1.0 + opts.threshold && ci.low > 1.0 {
+        Change::Regression
+    } else if ci.estimate < 1.0 - opts.threshold && ci.high < 1.0 {
+        Change::Improvement
+    } else {
+        Change::Noise
+    };
+
+    (change, ci)
+}
+
+fn index(
+    reports: Vec<ComparisonReport>,
+    language: Option<&str>,
+) -> BTreeMap<(String, String), BenchmarkResult> {
+    reports
+        .into_iter()
+        .flat_map(|r| r.results)
+        .filter(|r| language.is_none_or(|l| l == r.language))
+        .map(|r| ((r.name.clone(), r.language.clone()), r))
+        .collect()
+}
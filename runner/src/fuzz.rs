@@ -0,0 +1,637 @@
+//! Differential fuzzing of the bootstrap lexer and parser benchmarks.
+//!
+//! lex_bootstrap and parse_bootstrap implement the same BMB front end in
+//! C, Rust and BMB. The fuzzer splices generated BMB source into each
+//! implementation in place of its embedded sample, builds it, and compares
+//! the "Sample source:" summary every implementation prints (token counts
+//! for the lexer, AST node counts for the parser).
+//!
+//! Inputs the implementations disagree on are minimized and saved under
+//! `fuzz/<target>/` at the repository root. Saved cases are replayed at the
+//! start of every fuzz run.
+
+use crate::rng::Rng;
+use crate::validate;
+use colored::Colorize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Benchmarks that can be fuzzed.
+pub const TARGETS: &[&str] = &["lex_bootstrap", "parse_bootstrap"];
+
+/// The C lexer repeats its sample 500 times into a 500000-byte buffer.
+pub const MAX_INPUT_LEN: usize = 900;
+
+const RUN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Input every implementation is expected to handle; used to drop
+/// implementations whose toolchain is missing before fuzzing starts.
+const PROBE_INPUT: &str = "fn f(x: i64) -> i64 = x + 1;\n";
+
+#[derive(Debug, Clone, Copy)]
+pub enum Mode {
+    Random,
+    Grammar,
+    Mixed,
+}
+
+impl Mode {
+    pub fn parse(s: &str) -> Option<Mode> {
+        match s {
+            "random" => Some(Mode::Random),
+            "grammar" => Some(Mode::Grammar),
+            "mixed" => Some(Mode::Mixed),
+            _ => None,
+        }
+    }
+}
+
+pub struct FuzzOptions {
+    pub cases: u32,
+    pub mode: Mode,
+    pub seed: u64,
+    pub max_len: usize,
+    pub shrink_steps: u32,
+}
+
+/// Result of feeding one input to one implementation.
+#[derive(Debug, Clone, PartialEq)]
+enum Outcome {
+    Summary(BTreeMap<String, String>),
+    Failed(String),
+}
+
+/// One language implementation of a fuzz target, with its source template.
+struct Harness {
+    lang: &'static str,
+    template: String,
+    dir: PathBuf,
+}
+
+/// Fuzz one target. Returns the number of divergent inputs found
+/// (replayed regressions that still diverge included).
+pub fn run(target: &str, benches_dir: &Path, opts: &FuzzOptions) -> usize {
+    println!("{} {} (seed {})", "Fuzzing:".cyan().bold(), target, opts.seed);
+
+    let bench_path = benches_dir.join("bootstrap").join(target);
+    let work_dir = std::env::temp_dir()
+        .join(format!("benchmark-bmb-fuzz-{}", std::process::id()))
+        .join(target);
+
+    let mut harnesses = Vec::new();
+    for lang in ["c", "rust", "bmb"] {
+        let source = bench_path.join(lang).join(format!("main.{}", source_ext(lang)));
+        let Ok(template) = fs::read_to_string(&source) else {
+            continue;
+        };
+        if splice(lang, &template, PROBE_INPUT).is_none() {
+            println!("  {} {}: no embedded sample source found", "Skip:".yellow(), lang);
+            continue;
+        }
+        let harness = Harness {
+            lang,
+            template,
+            dir: work_dir.join(lang),
+        };
+        match harness.evaluate(PROBE_INPUT) {
+            Outcome::Summary(_) => harnesses.push(harness),
+            Outcome::Failed(reason) => {
+                println!("  {} {}: {}", "Skip:".yellow(), lang, reason);
+            }
+        }
+    }
+
+    if harnesses.len() < 2 {
+        println!("  {}", "Need at least two runnable implementations".yellow());
+        let _ = fs::remove_dir_all(&work_dir);
+        return 0;
+    }
+
+    let langs: Vec<_> = harnesses.iter().map(|h| h.lang).collect();
+    println!("  Implementations: {}", langs.join(", "));
+
+    let regression_dir = regression_dir(benches_dir, target);
+    let mut divergent = replay(&harnesses, &regression_dir);
+
+    let mut rng = Rng::new(opts.seed);
+    let max_len = opts.max_len.clamp(1, MAX_INPUT_LEN);
+
+    for case in 0..opts.cases {
+        let grammar = match opts.mode {
+            Mode::Random => false,
+            Mode::Grammar => true,
+            Mode::Mixed => case.is_multiple_of(2),
+        };
+        let input = if grammar {
+            generate_grammar(&mut rng, max_len)
+        } else {
+            generate_random(&mut rng, max_len)
+        };
+
+        let outcomes = evaluate_all(&harnesses, &input);
+        let Some(signature) = divergence(&outcomes) else {
+            continue;
+        };
+
+        divergent += 1;
+        println!(
+            "  {} case {} diverges on {}",
+            "✗".red(),
+            case + 1,
+            signature.iter().cloned().collect::<Vec<_>>().join(", ")
+        );
+
+        let minimized = minimize(&harnesses, &input, &signature, opts.shrink_steps);
+        let outcomes = evaluate_all(&harnesses, &minimized);
+        print_outcomes(&outcomes);
+
+        match save_regression(&regression_dir, &minimized) {
+            Ok(path) => println!(
+                "    minimized {} -> {} bytes, saved {}",
+                input.len(),
+                minimized.len(),
+                path.display()
+            ),
+            Err(e) => println!("    {} could not save case: {}", "Warning:".yellow(), e),
+        }
+    }
+
+    let _ = fs::remove_dir_all(&work_dir);
+
+    println!(
+        "  {}: {} cases, {} divergent",
+        "Summary".cyan().bold(),
+        opts.cases,
+        if divergent > 0 { divergent.to_string().red() } else { divergent.to_string().green() }
+    );
+    divergent
+}
+
+impl Harness {
+    fn evaluate(&self, input: &str) -> Outcome {
+        let Some(source) = splice(self.lang, &self.template, input) else {
+            return Outcome::Failed("splice failed".to_string());
+        };
+
+        if let Err(e) = fs::create_dir_all(&self.dir)
+            .and_then(|_| fs::write(self.dir.join(format!("main.{}", source_ext(self.lang))), source))
+        {
+            return Outcome::Failed(format!("write failed: {}", e));
+        }
+
+        let Some(executable) = crate::build_language(&self.dir, self.lang) else {
+            return Outcome::Failed("build failed".to_string());
+        };
+
+        match run_with_timeout(&executable) {
+            Ok(stdout) => match parse_summary(&stdout) {
+                Some(summary) => Outcome::Summary(summary),
+                None => Outcome::Failed("no sample summary in output".to_string()),
+            },
+            Err(reason) => Outcome::Failed(reason),
+        }
+    }
+}
+
+fn evaluate_all(harnesses: &[Harness], input: &str) -> Vec<(&'static str, Outcome)> {
+    harnesses.iter().map(|h| (h.lang, h.evaluate(input))).collect()
+}
+
+/// Keys on which the implementations disagree, or `None` if they agree.
+///
+/// Only keys every implementation reports are compared, since the BMB
+/// versions print a subset of the C and Rust statistics.
+fn divergence(outcomes: &[(&'static str, Outcome)]) -> Option<BTreeSet<String>> {
+    let mut signature = BTreeSet::new();
+
+    let summaries: Vec<_> = outcomes
+        .iter()
+        .filter_map(|(_, o)| match o {
+            Outcome::Summary(s) => Some(s),
+            Outcome::Failed(_) => None,
+        })
+        .collect();
+
+    if summaries.len() != outcomes.len() {
+        if summaries.is_empty() {
+            return None;
+        }
+        for (lang, outcome) in outcomes {
+            if let Outcome::Failed(_) = outcome {
+                signature.insert(format!("{} failed", lang));
+            }
+        }
+        return Some(signature);
+    }
+
+    let common: BTreeSet<&String> = summaries[0]
+        .keys()
+        .filter(|k| summaries.iter().all(|s| s.contains_key(*k)))
+        .collect();
+
+    for key in common {
+        if summaries.iter().any(|s| s[key] != summaries[0][key]) {
+            signature.insert(key.clone());
+        }
+    }
+
+    if signature.is_empty() {
+        None
+    } else {
+        Some(signature)
+    }
+}
+
+fn print_outcomes(outcomes: &[(&'static str, Outcome)]) {
+    for (lang, outcome) in outcomes {
+        let text = match outcome {
+            Outcome::Summary(summary) => summary
+                .iter()
+                .map(|(k, v)| format!("{}={}", k, v))
+                .collect::<Vec<_>>()
+                .join(", "),
+            Outcome::Failed(reason) => reason.clone(),
+        };
+        println!("    {:<6} {}", lang, text);
+    }
+}
+
+/// Shrink a divergent input while it keeps diverging on the same keys.
+/// Delta debugging over lines first, then over characters.
+fn minimize(harnesses: &[Harness], input: &str, signature: &BTreeSet<String>, budget: u32) -> String {
+    let mut budget = budget;
+    let mut still_diverges = |candidate: &str| {
+        divergence(&evaluate_all(harnesses, candidate)).as_ref() == Some(signature)
+    };
+
+    let lines: Vec<String> = input.split_inclusive('\n').map(String::from).collect();
+    let lines = ddmin(lines, &mut still_diverges, &mut budget);
+
+    let chars: Vec<String> = lines.concat().chars().map(String::from).collect();
+    ddmin(chars, &mut still_diverges, &mut budget).concat()
+}
+
+fn ddmin(
+    mut units: Vec<String>,
+    test: &mut impl FnMut(&str) -> bool,
+    budget: &mut u32,
+) -> Vec<String> {
+    let mut granularity = 2;
+
+    while units.len() >= 2 && *budget > 0 {
+        let chunk = units.len().div_ceil(granularity);
+        let mut reduced = false;
+
+        for start in (0..units.len()).step_by(chunk) {
+            if *budget == 0 {
+                break;
+            }
+            *budget -= 1;
+
+            let end = (start + chunk).min(units.len());
+            let candidate: Vec<String> = units[..start].iter().chain(&units[end..]).cloned().collect();
+            if test(&candidate.concat()) {
+                units = candidate;
+                granularity = (granularity - 1).max(2);
+                reduced = true;
+                break;
+            }
+        }
+
+        if !reduced {
+            if granularity >= units.len() {
+                break;
+            }
+            granularity = (granularity * 2).min(units.len());
+        }
+    }
+
+    units
+}
+
+/// Re-run every saved regression case. Returns how many still diverge.
+fn replay(harnesses: &[Harness], dir: &Path) -> usize {
+    let mut cases: Vec<_> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "bmb"))
+        .collect();
+    cases.sort();
+
+    if cases.is_empty() {
+        return 0;
+    }
+
+    println!("  Replaying {} saved case(s)", cases.len());
+    let mut divergent = 0;
+    for path in cases {
+        let Ok(input) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        match divergence(&evaluate_all(harnesses, &input)) {
+            Some(signature) => {
+                divergent += 1;
+                println!(
+                    "  {} {} still diverges on {}",
+                    "✗".red(),
+                    name,
+                    signature.into_iter().collect::<Vec<_>>().join(", ")
+                );
+            }
+            None => println!("  {} {} now agrees", "✓".green(), name),
+        }
+    }
+    divergent
+}
+
+fn regression_dir(benches_dir: &Path, target: &str) -> PathBuf {
+    benches_dir
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join("fuzz")
+        .join(target)
+}
+
+fn save_regression(dir: &Path, input: &str) -> std::io::Result<PathBuf> {
+    // FNV-1a keeps file names stable so the same case is never saved twice
+    let hash = input
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325u64, |h, b| (h ^ b as u64).wrapping_mul(0x0100_0000_01b3));
+
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!("case-{:016x}.bmb", hash));
+    if !path.exists() {
+        fs::write(&path, input)?;
+    }
+    Ok(path)
+}
+
+fn source_ext(lang: &str) -> &'static str {
+    match lang {
+        "c" => "c",
+        "rust" => "rs",
+        _ => "bmb",
+    }
+}
+
+/// Replace the embedded sample source of an implementation with `input`.
+fn splice(lang: &str, template: &str, input: &str) -> Option<String> {
+    let (start_marker, end_marker, literal) = match lang {
+        "c" => ("static const char *sample_source =", ";\n", c_literal(input)),
+        "rust" => ("const SAMPLE_SOURCE: &str =", "\"#;", format!("{:?}", input)),
+        "bmb" => ("fn sample_source() -> String =", ";\n", bmb_literal(input)),
+        _ => return None,
+    };
+
+    let start = template.find(start_marker)?;
+    let end = start + template[start..].find(end_marker)? + end_marker.trim_end().len();
+
+    Some(format!(
+        "{}{} {};{}",
+        &template[..start],
+        start_marker,
+        literal,
+        &template[end..]
+    ))
+}
+
+fn c_literal(input: &str) -> String {
+    let mut out = String::from("\"");
+    for b in input.bytes() {
+        match b {
+            b'\\' => out.push_str("\\\\"),
+            b'"' => out.push_str("\\\""),
+            b'\n' => out.push_str("\\n"),
+            b'\t' => out.push_str("\\t"),
+            0x20..=0x7e => out.push(b as char),
+            _ => out.push_str(&format!("\\{:03o}", b)),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// BMB string literals have no escapes in the bootstrap subset, so special
+/// characters are spelled as `char_to_string(chr(N))` pieces.
+fn bmb_literal(input: &str) -> String {
+    let mut pieces = Vec::new();
+    let mut run = String::new();
+
+    for c in input.chars() {
+        if (' '..='~').contains(&c) && c != '"' && c != '\\' {
+            run.push(c);
+            continue;
+        }
+        if !run.is_empty() {
+            pieces.push(format!("\"{}\"", run));
+            run.clear();
+        }
+        pieces.push(format!("char_to_string(chr({}))", c as u32));
+    }
+    if !run.is_empty() || pieces.is_empty() {
+        pieces.push(format!("\"{}\"", run));
+    }
+
+    pieces.join(" + ")
+}
+
+fn run_with_timeout(executable: &Path) -> Result<String, String> {
+    let mut child = Command::new(executable)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("failed to run: {}", e))?;
+
+    let mut stdout = child.stdout.take().ok_or("no stdout")?;
+    let reader = thread::spawn(move || {
+        let mut text = String::new();
+        let _ = stdout.read_to_string(&mut text);
+        text
+    });
+
+    let start = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if start.elapsed() > RUN_TIMEOUT => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err("timed out".to_string());
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(5)),
+            Err(e) => return Err(e.to_string()),
+        }
+    };
+
+    let text = reader.join().unwrap_or_default();
+    validate::completed(status)?;
+    Ok(text)
+}
+
+/// Parse the `Sample source:` block into key/value pairs.
+fn parse_summary(stdout: &str) -> Option<BTreeMap<String, String>> {
+    let mut lines = stdout.lines().skip_while(|l| l.trim() != "Sample source:");
+    lines.next()?;
+
+    let summary: BTreeMap<_, _> = lines
+        .take_while(|l| !l.trim().is_empty())
+        .filter_map(|l| l.split_once(':'))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect();
+
+    if summary.is_empty() {
+        None
+    } else {
+        Some(summary)
+    }
+}
+
+// Input generators
+
+const FRAGMENTS: &[&str] = &[
+    "fn", "let", "if", "else", "match", "struct", "enum", "pre", "post", "ret",
+    "->", "=>", "--", "::", "==", "<=", ">=", "!=", "&&", "||", "\"", "\\",
+    "x", "_", "i64", "0", "42", "3.14", " ", " ", "\n", "\t",
+];
+
+const SINGLE_CHARS: &str = "abcxyzABZ_0123456789+-*/%=<>!&|:.(){}[];,\"' \n";
+
+fn generate_random(rng: &mut Rng, max_len: usize) -> String {
+    let target = 1 + rng.below(max_len);
+    let chars: Vec<char> = SINGLE_CHARS.chars().collect();
+    let mut out = String::new();
+
+    while out.len() < target {
+        if rng.chance(40) {
+            let fragment = *rng.pick(FRAGMENTS);
+            out.push_str(fragment);
+        } else {
+            out.push(*rng.pick(&chars));
+        }
+    }
+
+    out.truncate(target);
+    out
+}
+
+const IDENTS: &[&str] = &["x", "y", "n", "acc", "value", "node", "tok_kind", "_tmp", "a1"];
+const FN_NAMES: &[&str] = &["fib", "compute", "process", "walk", "eval_expr", "helper"];
+const TYPES: &[&str] = &["i64", "f64", "bool", "String", "Option<i64>", "Token"];
+const BIN_OPS: &[&str] = &["+", "-", "*", "/", "%", "==", "!=", "<", "<=", ">", ">=", "and", "or"];
+
+fn generate_grammar(rng: &mut Rng, max_len: usize) -> String {
+    let mut out = String::new();
+
+    loop {
+        let item = match rng.below(10) {
+            0 => format!("-- {}\n", rng.pick(IDENTS)),
+            1 => gen_struct(rng),
+            2 => gen_enum(rng),
+            _ => gen_fn(rng),
+        };
+        if !out.is_empty() && out.len() + item.len() > max_len {
+            break;
+        }
+        out.push_str(&item);
+        if out.len() >= max_len || rng.chance(25) {
+            break;
+        }
+    }
+
+    out.truncate(max_len);
+    out
+}
+
+fn gen_fn(rng: &mut Rng) -> String {
+    let params: Vec<String> = (0..rng.below(4))
+        .map(|i| format!("{}{}: {}", rng.pick(IDENTS), i, rng.pick(TYPES)))
+        .collect();
+
+    let mut out = format!(
+        "fn {}({}) -> {}",
+        rng.pick(FN_NAMES),
+        params.join(", "),
+        rng.pick(TYPES)
+    );
+    if rng.chance(40) {
+        out.push_str(&format!("\n  pre {}", gen_expr(rng, 1)));
+    }
+    if rng.chance(30) {
+        out.push_str(&format!("\n  post ret {} {}", rng.pick(&[">=", "<", "=="]), gen_expr(rng, 0)));
+    }
+    out.push_str(&format!("\n= {};\n\n", gen_expr(rng, 3)));
+    out
+}
+
+fn gen_struct(rng: &mut Rng) -> String {
+    let fields: Vec<String> = (0..1 + rng.below(3))
+        .map(|i| format!("    {}{}: {}", rng.pick(IDENTS), i, rng.pick(TYPES)))
+        .collect();
+    format!("struct Token{} {{\n{}\n}}\n\n", rng.below(10), fields.join(",\n"))
+}
+
+fn gen_enum(rng: &mut Rng) -> String {
+    let variants: Vec<String> = (0..1 + rng.below(3))
+        .map(|i| {
+            if rng.chance(50) {
+                format!("    V{}({})", i, rng.pick(TYPES))
+            } else {
+                format!("    V{}", i)
+            }
+        })
+        .collect();
+    format!("enum Kind{}<T> {{\n{}\n}}\n\n", rng.below(10), variants.join(",\n"))
+}
+
+fn gen_expr(rng: &mut Rng, depth: u32) -> String {
+    let choice = if depth == 0 { rng.below(4) } else { rng.below(11) };
+
+    match choice {
+        0 => rng.below(1000).to_string(),
+        1 => rng.pick(IDENTS).to_string(),
+        2 => format!("{}.{}", rng.below(100), rng.below(100)),
+        3 => if rng.chance(50) { "true" } else { "\"s\"" }.to_string(),
+        4 | 5 => format!(
+            "{} {} {}",
+            gen_expr(rng, depth - 1),
+            rng.pick(BIN_OPS),
+            gen_expr(rng, depth - 1)
+        ),
+        6 => format!(
+            "if {} {{ {} }} else {{ {} }}",
+            gen_expr(rng, depth - 1),
+            gen_expr(rng, depth - 1),
+            gen_expr(rng, depth - 1)
+        ),
+        7 => format!(
+            "{}({})",
+            rng.pick(FN_NAMES),
+            (0..rng.below(3))
+                .map(|_| gen_expr(rng, depth - 1))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        8 => format!(
+            "match {} {{\n        Some({}) => {},\n        None => {}\n    }}",
+            rng.pick(IDENTS),
+            rng.pick(IDENTS),
+            gen_expr(rng, depth - 1),
+            gen_expr(rng, depth - 1)
+        ),
+        9 => format!(
+            "{{\n    let {} = {};\n    {}\n}}",
+            rng.pick(IDENTS),
+            gen_expr(rng, depth - 1),
+            gen_expr(rng, depth - 1)
+        ),
+        _ => format!("({})", gen_expr(rng, depth - 1)),
+    }
+}
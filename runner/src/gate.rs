@@ -0,0 +1,1051 @@
+//! Benchmark gates defined in `gates.toml`.
+//!
+//! Each gate names a baseline and a subject implementation and one or more
+//! groups of benchmarks with a ratio threshold and quantifier rules. A
+//! regression gate instead compares the subject with its own samples in a
+//! pinned earlier run, so gains already made cannot quietly erode.
+//! Benchmarks are selected by name or tag; every selected benchmark is
+//! validated before it is timed, so a wrong answer can never count as a pass.
+//!
+//! Verdicts use the confidence interval of each ratio, not its point
+//! estimate: a benchmark passes only if the whole interval is within its
+//! limit and fails only if the whole interval is beyond it. Intervals that
+//! straddle the limit are resampled within a time budget, and whatever is
+//! still undecided makes the verdict INCONCLUSIVE rather than a coin flip.
+
+use crate::manifest;
+use crate::results::RunDocument;
+use crate::stats::Interval;
+use crate::{schedule, stats, validate, ComparisonReport};
+use colored::Colorize;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+pub const GATES_FILE: &str = "gates.toml";
+
+/// Warmup runs before each gate measurement
+const GATE_WARMUP: u32 = 2;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GateFile {
+    #[serde(rename = "gate", default)]
+    pub gates: Vec<GateDef>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GateDef {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Implementations (`lang` or `lang:variant`) the subject is compared
+    /// with; with several, against the fastest of them per benchmark
+    #[serde(rename = "baseline", default = "default_baseline", deserialize_with = "one_or_many")]
+    pub baselines: Vec<String>,
+    #[serde(default = "default_subject")]
+    pub subject: String,
+    #[serde(default)]
+    pub kind: GateKind,
+    /// Run a regression gate compares against: a history session label,
+    /// git revision or BMB version, or a result file
+    #[serde(default)]
+    pub against: Option<String>,
+    #[serde(rename = "group", default)]
+    pub groups: Vec<GroupDef>,
+}
+
+/// What a gate's ratios are taken against.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GateKind {
+    /// Subject / baseline language, measured side by side
+    #[default]
+    Ratio,
+    /// Subject now / subject in the pinned run
+    Regression,
+}
+
+/// Earlier run a regression gate compares against.
+pub struct Pinned {
+    pub source: PathBuf,
+    pub doc: RunDocument,
+}
+
+impl Pinned {
+    /// Samples of one implementation of a benchmark in the pinned run.
+    fn samples(&self, benchmark: &str, language: &str) -> Option<&[f64]> {
+        self.doc
+            .reports
+            .iter()
+            .filter(|r| r.benchmark == benchmark)
+            .flat_map(|r| &r.results)
+            .find(|r| r.language == language && !r.times_ms.is_empty())
+            .map(|r| r.times_ms.as_slice())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GroupDef {
+    pub name: String,
+    #[serde(default)]
+    pub benchmarks: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Highest passing subject/baseline ratio
+    pub threshold: f64,
+    /// Per-benchmark overrides of `threshold`
+    #[serde(default)]
+    pub thresholds: BTreeMap<String, f64>,
+    /// Ratio below which a benchmark is marked ★ as faster than the
+    /// baseline; it does not affect the verdict
+    #[serde(default = "default_faster")]
+    pub faster: f64,
+    /// Quantifiers that must all hold; every benchmark within its threshold
+    /// by default
+    #[serde(default = "default_rules")]
+    pub rules: Vec<Rule>,
+}
+
+/// How a group's per-benchmark ratios combine into a verdict.
+///
+/// ```toml
+/// rules = ["all", { at-least = 3 }, { geomean-below = 0.95 }, { none-worse-than = 1.10 }]
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Rule {
+    /// Every benchmark within its threshold
+    All,
+    /// At least N benchmarks within their thresholds
+    AtLeast(usize),
+    /// Geometric mean of the ratios strictly below the value
+    GeomeanBelow(f64),
+    /// No ratio above the value
+    NoneWorseThan(f64),
+}
+
+/// Verdict of a benchmark, a rule or a whole gate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    Pass,
+    Fail,
+    /// The confidence interval straddles the limit
+    Inconclusive,
+}
+
+impl Verdict {
+    /// Compare a ratio interval with a limit: pass only if the upper bound
+    /// is within it, fail only if the lower bound is beyond it.
+    fn of(ci: &Interval, limit: f64) -> Self {
+        if ci.high <= limit {
+            Verdict::Pass
+        } else if ci.low > limit {
+            Verdict::Fail
+        } else {
+            Verdict::Inconclusive
+        }
+    }
+
+    /// Verdict of a condition evaluated on the pessimistic and optimistic
+    /// ends of every interval.
+    fn bounded(pessimistic: bool, optimistic: bool) -> Self {
+        match (pessimistic, optimistic) {
+            (true, _) => Verdict::Pass,
+            (false, false) => Verdict::Fail,
+            (false, true) => Verdict::Inconclusive,
+        }
+    }
+
+    fn symbol(&self) -> colored::ColoredString {
+        match self {
+            Verdict::Pass => "✓".green(),
+            Verdict::Fail => "✗".red(),
+            Verdict::Inconclusive => "?".yellow(),
+        }
+    }
+}
+
+/// Verdict of one rule on one group.
+#[derive(Debug, Serialize)]
+pub struct RuleOutcome {
+    pub group: String,
+    /// What the rule requires, e.g. "at least 3 within 1.00x"
+    pub requirement: String,
+    /// What was measured, e.g. "2 of 6, 1 inconclusive"
+    pub observed: String,
+    pub verdict: Verdict,
+}
+
+/// One benchmark of a gate run, as written by `--format json|junit`.
+#[derive(Debug, Serialize)]
+pub struct GateCase {
+    pub group: String,
+    pub benchmark: String,
+    pub category: String,
+    /// Implementation the ratio was taken against (the fastest baseline,
+    /// or "pinned" for a regression gate)
+    pub baseline: Option<String>,
+    /// None if the benchmark could not be built
+    pub verdict: Option<Verdict>,
+    /// Why the benchmark has no timings (validation failure, build failure)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub threshold: f64,
+    /// Subject/baseline ratio of medians and its confidence interval
+    pub ratio: Option<f64>,
+    pub ci_low: Option<f64>,
+    pub ci_high: Option<f64>,
+    pub baseline_samples_ms: Vec<f64>,
+    pub subject_samples_ms: Vec<f64>,
+}
+
+impl GateCase {
+    fn unmeasured(group: &GroupDef, benchmark: &str, bench_path: &Path, verdict: Option<Verdict>, error: String) -> Self {
+        GateCase {
+            group: group.name.clone(),
+            benchmark: benchmark.to_string(),
+            category: manifest::category(bench_path),
+            baseline: None,
+            verdict,
+            error: Some(error),
+            threshold: group.threshold_for(benchmark),
+            ratio: None,
+            ci_low: None,
+            ci_high: None,
+            baseline_samples_ms: Vec::new(),
+            subject_samples_ms: Vec::new(),
+        }
+    }
+}
+
+/// Ratio interval of one measured benchmark against its limit
+struct GroupResult<'a> {
+    name: &'a str,
+    ci: Interval,
+    threshold: f64,
+}
+
+impl Rule {
+    /// What the rule requires, e.g. "at least 3 within 1.00x".
+    fn requirement(&self, group: &GroupDef) -> String {
+        let limit = if group.thresholds.is_empty() {
+            format!("{:.2}x", group.threshold)
+        } else {
+            "limit".to_string()
+        };
+        match *self {
+            Rule::All => format!("all within {}", limit),
+            Rule::AtLeast(n) => format!("at least {} within {}", n, limit),
+            Rule::GeomeanBelow(max) => format!("geomean below {:.2}x", max),
+            Rule::NoneWorseThan(max) => format!("none worse than {:.2}x", max),
+        }
+    }
+
+    /// Evaluate on the upper bounds (pass) and the lower bounds (fail) of
+    /// the ratio intervals; anything in between is inconclusive. Benchmarks
+    /// in `unmeasured` could not be built or run and those in `invalid`
+    /// failed validation; both count as over their limit, so a group cannot
+    /// pass on whatever happened to build and answer correctly.
+    fn evaluate(&self, group: &GroupDef, results: &[GroupResult], unmeasured: &[&str], invalid: &[&str]) -> RuleOutcome {
+        let total = results.len() + unmeasured.len() + invalid.len();
+        let sure = results.iter().filter(|r| r.ci.high <= r.threshold).count();
+        let possible = results.iter().filter(|r| r.ci.low <= r.threshold).count();
+        let mut counted = if possible > sure {
+            format!("{} of {}, {} inconclusive", sure, total, possible - sure)
+        } else {
+            format!("{} of {}", sure, total)
+        };
+        let mut missing = match unmeasured {
+            [] => String::new(),
+            names => format!(", not measured: {}", names.join(", ")),
+        };
+        if !invalid.is_empty() {
+            missing.push_str(&format!(", failed validation: {}", invalid.join(", ")));
+        }
+        let complete = unmeasured.is_empty() && invalid.is_empty();
+        counted.push_str(&missing);
+        let requirement = self.requirement(group);
+        let group = group.name.clone();
+        let worst = results.iter().max_by(|a, b| a.ci.estimate.total_cmp(&b.ci.estimate));
+
+        match *self {
+            Rule::All => {
+                let mut observed = counted;
+                if let Some(w) = worst.filter(|w| w.ci.estimate > w.threshold) {
+                    observed.push_str(&format!(", worst {} {:.2}x", w.name, w.ci.estimate));
+                }
+                RuleOutcome {
+                    group,
+                    verdict: Verdict::bounded(sure == total, possible == total),
+                    requirement,
+                    observed,
+                }
+            }
+            Rule::AtLeast(n) => RuleOutcome {
+                group,
+                verdict: Verdict::bounded(sure >= n, possible >= n),
+                requirement,
+                observed: counted,
+            },
+            Rule::GeomeanBelow(max) => {
+                let geomean = |bound: fn(&Interval) -> f64| {
+                    stats::geomean(&results.iter().map(|r| bound(&r.ci)).collect::<Vec<_>>())
+                };
+                let ci = Interval {
+                    estimate: geomean(|ci| ci.estimate),
+                    low: geomean(|ci| ci.low),
+                    high: geomean(|ci| ci.high),
+                };
+                RuleOutcome {
+                    group,
+                    // The geometric mean is undefined without every ratio
+                    verdict: if results.is_empty() || !complete {
+                        Verdict::Fail
+                    } else {
+                        Verdict::bounded(ci.high < max, ci.low < max)
+                    },
+                    requirement,
+                    observed: if results.is_empty() {
+                        format!("nothing measured{}", missing)
+                    } else {
+                        format!(
+                            "geomean {:.3}x [{:.3}, {:.3}] over {}{}",
+                            ci.estimate,
+                            ci.low,
+                            ci.high,
+                            results.len(),
+                            missing
+                        )
+                    },
+                }
+            }
+            Rule::NoneWorseThan(max) => RuleOutcome {
+                group,
+                verdict: Verdict::bounded(
+                    complete && results.iter().all(|r| r.ci.high <= max),
+                    complete && results.iter().all(|r| r.ci.low <= max),
+                ),
+                requirement,
+                observed: match worst {
+                    Some(w) => format!(
+                        "worst {} {:.2}x [{:.2}, {:.2}]{}",
+                        w.name, w.ci.estimate, w.ci.low, w.ci.high, missing
+                    ),
+                    None => format!("nothing measured{}", missing),
+                },
+            },
+        }
+    }
+
+    fn limit(&self) -> Option<f64> {
+        match *self {
+            Rule::GeomeanBelow(x) | Rule::NoneWorseThan(x) => Some(x),
+            Rule::All | Rule::AtLeast(_) => None,
+        }
+    }
+}
+
+fn default_faster() -> f64 {
+    1.0
+}
+
+fn default_rules() -> Vec<Rule> {
+    vec![Rule::All]
+}
+
+fn default_baseline() -> Vec<String> {
+    vec!["c".to_string()]
+}
+
+/// `baseline = "c"` or `baseline = ["c", "rust"]`
+fn one_or_many<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(one) => vec![one],
+        OneOrMany::Many(many) => many,
+    })
+}
+
+fn default_subject() -> String {
+    "bmb".to_string()
+}
+
+impl GateFile {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let file: GateFile = toml::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        for (i, gate) in file.gates.iter().enumerate() {
+            if file.gates[..i].iter().any(|g| g.id == gate.id) {
+                return Err(format!("{}: gate '{}' is defined twice", path.display(), gate.id));
+            }
+            match (gate.kind, &gate.against) {
+                (GateKind::Regression, None) => {
+                    return Err(format!("{}: regression gate '{}' needs `against`", path.display(), gate.id));
+                }
+                (GateKind::Ratio, Some(_)) => {
+                    return Err(format!(
+                        "{}: gate '{}' sets `against` but is not a regression gate",
+                        path.display(),
+                        gate.id
+                    ));
+                }
+                _ => {}
+            }
+            if gate.baselines.is_empty() || gate.baselines.contains(&gate.subject) {
+                return Err(format!(
+                    "{}: gate '{}' needs a baseline other than its subject",
+                    path.display(),
+                    gate.id
+                ));
+            }
+            if gate.groups.is_empty() {
+                return Err(format!("{}: gate '{}' has no groups", path.display(), gate.id));
+            }
+            for group in &gate.groups {
+                if group.benchmarks.is_empty() && group.tags.is_empty() {
+                    return Err(format!(
+                        "{}: group '{}' of gate '{}' selects no benchmarks",
+                        path.display(),
+                        group.name,
+                        gate.id
+                    ));
+                }
+                if group.rules.is_empty() || group.rules.contains(&Rule::AtLeast(0)) {
+                    return Err(format!(
+                        "{}: group '{}' of gate '{}' needs at least one rule, and at-least must be positive",
+                        path.display(),
+                        group.name,
+                        gate.id
+                    ));
+                }
+            }
+        }
+        Ok(file)
+    }
+
+    /// Gate by id ("3.1") or name ("Gate #3.1").
+    pub fn find(&mut self, id: &str) -> Option<&mut GateDef> {
+        self.gates.iter_mut().find(|g| g.id == id || g.name == id)
+    }
+
+    pub fn ids(&self) -> Vec<&str> {
+        self.gates.iter().map(|g| g.id.as_str()).collect()
+    }
+}
+
+/// `gates.toml` next to the `benches/` directory.
+pub fn gates_path(benches_dir: &Path) -> PathBuf {
+    benches_dir.parent().unwrap_or(Path::new(".")).join(GATES_FILE)
+}
+
+impl GroupDef {
+    fn threshold_for(&self, benchmark: &str) -> f64 {
+        self.thresholds.get(benchmark).copied().unwrap_or(self.threshold)
+    }
+
+    /// Benchmarks selected by name or tag, in discovery order, each once.
+    /// Every named benchmark must exist in exactly one category, threshold
+    /// overrides must name selected benchmarks, and something must be
+    /// selected.
+    fn resolve(&self, discovered: &[(String, PathBuf)]) -> Result<Vec<(String, PathBuf)>, String> {
+        for name in &self.benchmarks {
+            let matches: Vec<&PathBuf> = discovered.iter().filter(|(n, _)| n == name).map(|(_, p)| p).collect();
+            match matches.as_slice() {
+                [] => {
+                    let hint = discovered
+                        .iter()
+                        .find(|(n, _)| n.replace('-', "_") == name.replace('-', "_"))
+                        .map(|(n, _)| format!(" (did you mean '{}'?)", n))
+                        .unwrap_or_default();
+                    return Err(format!("group '{}': unknown benchmark '{}'{}", self.name, name, hint));
+                }
+                [_] => {}
+                _ => {
+                    let categories: Vec<String> = matches.iter().map(|p| manifest::category(p)).collect();
+                    return Err(format!(
+                        "group '{}': benchmark '{}' is ambiguous, it exists in {}",
+                        self.name,
+                        name,
+                        categories.join(", ")
+                    ));
+                }
+            }
+        }
+
+        let selected: Vec<(String, PathBuf)> = discovered
+            .iter()
+            .filter(|(name, path)| {
+                self.benchmarks.contains(name) || manifest::tags(path).iter().any(|t| self.tags.contains(t))
+            })
+            .cloned()
+            .collect();
+
+        if selected.is_empty() {
+            return Err(format!("group '{}': tags {:?} match no benchmarks", self.name, self.tags));
+        }
+        if let Some(name) = self.thresholds.keys().find(|k| !selected.iter().any(|(n, _)| n == *k)) {
+            return Err(format!(
+                "group '{}': threshold override for '{}', which the group does not select",
+                self.name, name
+            ));
+        }
+        Ok(selected)
+    }
+}
+
+impl GateDef {
+    /// Display name of what the subject is compared with: "C", or
+    /// "best(C, RUST)" for several baselines.
+    fn reference(&self) -> String {
+        let names: Vec<String> = self.baselines.iter().map(|b| b.to_uppercase()).collect();
+        match names.as_slice() {
+            [one] => one.clone(),
+            many => format!("best({})", many.join(", ")),
+        }
+    }
+
+    /// Benchmarks of every group, resolved against `benches_dir`.
+    pub fn resolve(&self, benches_dir: &Path) -> Result<Vec<Vec<(String, PathBuf)>>, String> {
+        let discovered = crate::discover_benchmarks(benches_dir, None);
+        self.groups
+            .iter()
+            .map(|group| group.resolve(&discovered))
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("{}: {}", self.name, e))
+    }
+
+    /// Smallest distance from 1.0 of any limit a group must resolve.
+    /// Parity limits (exactly 1.0) are left out: A/A noise is centred on
+    /// 1.0 and does not push a ratio across them. `None` if every limit is
+    /// a parity limit.
+    pub fn tightest_margin(&self) -> Option<f64> {
+        self.groups
+            .iter()
+            .flat_map(|g| {
+                std::iter::once(g.threshold)
+                    .chain(g.thresholds.values().copied())
+                    .chain(g.rules.iter().filter_map(Rule::limit))
+            })
+            .map(|t| (t - 1.0).abs())
+            .filter(|margin| *margin > 1e-9)
+            .min_by(f64::total_cmp)
+    }
+
+    /// Raise every limit of a regression gate to at least `1 + floor`, so
+    /// a slowdown this machine cannot tell from noise never fails it.
+    /// Returns whether any limit was raised.
+    pub fn apply_noise_floor(&mut self, floor: f64) -> bool {
+        let lowest = 1.0 + floor;
+        let mut raised = false;
+        let mut raise = |limit: &mut f64| {
+            if *limit < lowest {
+                *limit = lowest;
+                raised = true;
+            }
+        };
+        for group in &mut self.groups {
+            raise(&mut group.threshold);
+            group.thresholds.values_mut().for_each(&mut raise);
+            for rule in &mut group.rules {
+                if let Rule::GeomeanBelow(x) | Rule::NoneWorseThan(x) = rule {
+                    raise(x);
+                }
+            }
+        }
+        raised
+    }
+}
+
+/// How many samples a gate takes.
+pub struct Sampling {
+    pub iterations: u32,
+    /// Confidence level of the ratio intervals
+    pub confidence: f64,
+    /// Time the whole gate may spend resampling inconclusive benchmarks
+    pub budget: Duration,
+}
+
+/// Outcome of one gate run.
+pub struct GateOutcome {
+    pub verdict: Verdict,
+    pub passed_count: usize,
+    pub failed_count: usize,
+    pub inconclusive_count: usize,
+    pub excluded_count: usize,
+    pub rules: Vec<RuleOutcome>,
+    pub cases: Vec<GateCase>,
+    pub reports: Vec<ComparisonReport>,
+}
+
+/// Outcome of measuring one benchmark for a gate
+enum GateMeasurement {
+    /// Outputs agree and every implementation was timed
+    Measured {
+        /// Samples of every baseline in gate order (the pinned samples for
+        /// a regression gate)
+        baseline_times: Vec<Vec<f64>>,
+        subject_times: Vec<f64>,
+        /// Rounds of extra samples taken because the first were inconclusive
+        extra_rounds: u32,
+    },
+    /// Outputs disagree; the benchmark cannot carry the gate
+    Invalid(String),
+    /// A baseline or the subject implementation could not be built
+    Unavailable,
+}
+
+pub fn print_header(gate: &GateDef) {
+    println!("{}: {}", gate.name.green().bold(), gate.description);
+    let ratio = match gate.kind {
+        GateKind::Ratio => format!("{}/{}", gate.subject.to_uppercase(), gate.reference()),
+        GateKind::Regression => format!("{} now/pinned", gate.subject.to_uppercase()),
+    };
+    for group in &gate.groups {
+        let rules: Vec<String> = group
+            .rules
+            .iter()
+            .map(|rule| rule.requirement(group))
+            .collect();
+        println!(
+            "{} threshold: {} <= {:.2}x ({})",
+            group.name,
+            ratio,
+            group.threshold,
+            rules.join(", ")
+        );
+    }
+    println!();
+}
+
+/// Measure a gate whose groups were resolved by [`GateDef::resolve`].
+/// Regression gates take their baseline samples from `pinned`.
+pub fn verify(
+    gate: &GateDef,
+    selected: &[Vec<(String, PathBuf)>],
+    sampling: &Sampling,
+    pinned: Option<&Pinned>,
+    verbose: bool,
+) -> GateOutcome {
+    let (reference, baseline_names) = match pinned {
+        Some(_) => ("the pinned run".to_string(), vec!["pinned".to_string()]),
+        None => (gate.reference(), gate.baselines.clone()),
+    };
+    // One time column per baseline, and which one was fastest if several
+    let mut time_labels: Vec<String> = baseline_names.iter().map(|b| format!("{} (ms)", b.to_uppercase())).collect();
+    time_labels.push(format!("{} (ms)", gate.subject.to_uppercase()));
+    let columns: Vec<usize> = time_labels.iter().map(|label| label.len().max(10)).collect();
+    let best_column = baseline_names.len() > 1;
+    let width = 68 + columns.iter().map(|w| w + 1).sum::<usize>() + if best_column { 11 } else { 0 };
+    let ci_label = format!("{:.0}% CI", sampling.confidence * 100.0);
+    let mut budget = sampling.budget;
+
+    let mut passed = 0;
+    let mut failed = 0;
+    let mut inconclusive = 0;
+    let mut excluded = 0;
+    let mut faster_count = 0;
+    let mut reports = Vec::new();
+    let mut cases = Vec::new();
+    let mut verdicts = Vec::new();
+
+    for (i, (group, benchmarks)) in gate.groups.iter().zip(selected).enumerate() {
+        if i > 0 {
+            println!();
+        }
+        println!("{}", format!("{} Benchmarks:", group.name).cyan());
+        println!("{}", "-".repeat(width));
+        print!("{:<20}", "Benchmark");
+        for (label, w) in time_labels.iter().zip(&columns) {
+            print!(" {:>w$}", label);
+        }
+        if best_column {
+            print!(" {:>10}", "Best");
+        }
+        println!(" {:>8} {:>16} {:>8} {:>8}", "Ratio", ci_label, "Limit", "Status");
+        println!("{}", "-".repeat(width));
+
+        let mut results = Vec::new();
+        let mut unmeasured = Vec::new();
+        let mut invalid = Vec::new();
+        for (bench_name, bench_path) in benchmarks {
+            let threshold = group.threshold_for(bench_name);
+
+            let pinned_times = match pinned {
+                Some(run) => match run.samples(bench_name, &gate.subject) {
+                    Some(times) => Some(times),
+                    None => {
+                        let reason = format!("no {} samples in {}", gate.subject, run.source.display());
+                        println!("{:<20} {} ({})", bench_name, "Not in pinned run".yellow(), reason);
+                        unmeasured.push(bench_name.as_str());
+                        cases.push(GateCase::unmeasured(group, bench_name, bench_path, None, reason));
+                        continue;
+                    }
+                },
+                None => None,
+            };
+
+            match measure(bench_path, gate, threshold, sampling, pinned_times, &mut budget) {
+                GateMeasurement::Measured {
+                    baseline_times,
+                    subject_times,
+                    extra_rounds,
+                } => {
+                    let best = fastest(&baseline_times);
+                    let ci = stats::ratio_ci(&subject_times, &baseline_times[best], sampling.confidence);
+                    let verdict = Verdict::of(&ci, threshold);
+                    match verdict {
+                        Verdict::Pass => passed += 1,
+                        Verdict::Fail => failed += 1,
+                        Verdict::Inconclusive => inconclusive += 1,
+                    }
+                    let mut symbol = verdict.symbol().to_string();
+                    if ci.high < group.faster {
+                        faster_count += 1;
+                        symbol.push_str(&"★".green().to_string());
+                    }
+                    print!("{:<20}", bench_name);
+                    for (times, w) in baseline_times.iter().chain([&subject_times]).zip(&columns) {
+                        print!(" {:>w$.2}", stats::median(times));
+                    }
+                    if best_column {
+                        print!(" {:>10}", baseline_names[best].to_uppercase());
+                    }
+                    println!(
+                        " {:>7.2}x {:>16} {:>7.2}x {:>8}",
+                        ci.estimate,
+                        format!("[{:.2}, {:.2}]", ci.low, ci.high),
+                        threshold,
+                        symbol
+                    );
+                    if extra_rounds > 0 {
+                        println!(
+                            "  resampled {} time(s), {} samples per side",
+                            extra_rounds,
+                            subject_times.len()
+                        );
+                    }
+
+                    cases.push(GateCase {
+                        group: group.name.clone(),
+                        benchmark: bench_name.clone(),
+                        category: manifest::category(bench_path),
+                        baseline: Some(baseline_names[best].clone()),
+                        verdict: Some(verdict),
+                        error: None,
+                        threshold,
+                        ratio: Some(ci.estimate),
+                        ci_low: Some(ci.low),
+                        ci_high: Some(ci.high),
+                        baseline_samples_ms: baseline_times[best].clone(),
+                        subject_samples_ms: subject_times.clone(),
+                    });
+                    // The pinned samples are already stored with their own run
+                    let mut measurements: Vec<(String, Vec<f64>)> = match pinned {
+                        Some(_) => Vec::new(),
+                        None => gate.baselines.iter().cloned().zip(baseline_times).collect(),
+                    };
+                    measurements.push((gate.subject.clone(), subject_times));
+                    reports.push(ComparisonReport::from_measurements(bench_path, &measurements, GATE_WARMUP));
+                    results.push(GroupResult {
+                        name: bench_name,
+                        ci,
+                        threshold,
+                    });
+                }
+                GateMeasurement::Invalid(reason) => {
+                    failed += 1;
+                    excluded += 1;
+                    print!("{:<20}", bench_name);
+                    for w in &columns {
+                        print!(" {:>w$}", "-");
+                    }
+                    if best_column {
+                        print!(" {:>10}", "-");
+                    }
+                    println!(" {:>8} {:>16} {:>7.2}x {:>8}", "-", "-", threshold, "✗".red());
+                    println!("  {} {}", "FAIL:".red(), reason);
+                    invalid.push(bench_name.as_str());
+                    cases.push(GateCase::unmeasured(group, bench_name, bench_path, Some(Verdict::Fail), reason));
+                }
+                GateMeasurement::Unavailable => {
+                    println!("{:<20} {}", bench_name, "Not built or not runnable".yellow());
+                    unmeasured.push(bench_name.as_str());
+                    let reason = match pinned {
+                        Some(_) => format!("{} implementation could not be built or run", gate.subject),
+                        None => format!(
+                            "{} or {} implementation could not be built or run",
+                            gate.baselines.join(", "),
+                            gate.subject
+                        ),
+                    };
+                    if verbose {
+                        println!("  {}", reason);
+                    }
+                    cases.push(GateCase::unmeasured(group, bench_name, bench_path, None, reason));
+                }
+            }
+        }
+
+        for rule in &group.rules {
+            verdicts.push(rule.evaluate(group, &results, &unmeasured, &invalid));
+        }
+    }
+
+    // Summary
+    println!();
+    println!("{}", "=".repeat(width));
+    println!(
+        "{}: {} passed, {} failed, {} inconclusive, {} faster than {}, {} excluded for correctness",
+        "Summary".cyan().bold(),
+        passed.to_string().green(),
+        if failed > 0 { failed.to_string().red() } else { failed.to_string().green() },
+        if inconclusive > 0 { inconclusive.to_string().yellow() } else { inconclusive.to_string().green() },
+        faster_count.to_string().cyan(),
+        reference,
+        if excluded > 0 { excluded.to_string().red() } else { excluded.to_string().green() }
+    );
+
+    // A wrong answer fails the gate whatever the quantifiers say
+    for rule in &verdicts {
+        println!(
+            "  {} {}: {} — {}",
+            rule.verdict.symbol(),
+            rule.group,
+            rule.requirement,
+            rule.observed
+        );
+    }
+    if excluded > 0 {
+        println!("  {} correctness: every benchmark must validate — {} did not", "✗".red(), excluded);
+    }
+    let verdict = if excluded > 0 || verdicts.iter().any(|r| r.verdict == Verdict::Fail) {
+        Verdict::Fail
+    } else if verdicts.iter().any(|r| r.verdict == Verdict::Inconclusive) {
+        Verdict::Inconclusive
+    } else {
+        Verdict::Pass
+    };
+
+    GateOutcome {
+        verdict,
+        passed_count: passed,
+        failed_count: failed,
+        inconclusive_count: inconclusive,
+        excluded_count: excluded,
+        rules: verdicts,
+        cases,
+        reports,
+    }
+}
+
+/// Index of the baseline with the lowest median.
+fn fastest(baseline_times: &[Vec<f64>]) -> usize {
+    (0..baseline_times.len())
+        .min_by(|&a, &b| stats::median(&baseline_times[a]).total_cmp(&stats::median(&baseline_times[b])))
+        .unwrap_or(0)
+}
+
+/// Build, validate and time one benchmark. With `pinned` samples only the
+/// subject is timed and compared against them. Extra rounds for an
+/// inconclusive interval are charged to `budget`.
+fn measure(
+    bench_path: &Path,
+    gate: &GateDef,
+    threshold: f64,
+    sampling: &Sampling,
+    pinned: Option<&[f64]>,
+    budget: &mut Duration,
+) -> GateMeasurement {
+    let Some(subject_exe) = crate::build_implementation(bench_path, &gate.subject) else {
+        return GateMeasurement::Unavailable;
+    };
+    let mut baseline_exes = Vec::new();
+    if pinned.is_some() {
+        // Only the subject is timed, so a baseline is built just to validate
+        // against when there is no expected output, and is not required
+        let validated = match validate::expected(bench_path) {
+            Some(expected) => validate::capture(&subject_exe).and_then(|answer| validate::compare(&expected, &answer)),
+            None => match gate.baselines.first().and_then(|b| crate::build_implementation(bench_path, b)) {
+                Some(reference) => validate::check(bench_path, &reference, &subject_exe),
+                None => Ok(()),
+            },
+        };
+        if let Err(reason) = validated {
+            return GateMeasurement::Invalid(reason);
+        }
+    } else {
+        for baseline in &gate.baselines {
+            match crate::build_implementation(bench_path, baseline) {
+                Some(exe) => baseline_exes.push(exe),
+                None => return GateMeasurement::Unavailable,
+            }
+        }
+
+        // Validate before timing so a wrong answer can never count as a pass.
+        // Further baselines are checked too: a wrong one must not be "fastest".
+        if let Err(reason) = validate::check(bench_path, &baseline_exes[0], &subject_exe) {
+            return GateMeasurement::Invalid(reason);
+        }
+        for (baseline, exe) in gate.baselines.iter().zip(&baseline_exes).skip(1) {
+            if let Err(reason) = validate::check(bench_path, &baseline_exes[0], exe) {
+                return GateMeasurement::Invalid(format!("baseline {}: {}", baseline, reason));
+            }
+        }
+    }
+
+    let executables: Vec<&Path> = baseline_exes.iter().map(PathBuf::as_path).chain([subject_exe.as_path()]).collect();
+    let mut times = schedule::measure(&executables, sampling.iterations, GATE_WARMUP);
+    if let Some(pinned) = pinned {
+        times.insert(0, pinned.to_vec());
+    }
+    let timed_from = times.len() - executables.len();
+    let mut extra_rounds = 0;
+
+    // An interval straddling the limit is resampled until it clears the
+    // limit one way or the other, or the gate's budget runs out
+    let inconclusive = |times: &[Vec<f64>]| match times.split_last() {
+        Some((subject, baselines)) if !baselines.is_empty() => {
+            let ci = stats::ratio_ci(subject, &baselines[fastest(baselines)], sampling.confidence);
+            Verdict::of(&ci, threshold) == Verdict::Inconclusive
+        }
+        _ => false,
+    };
+    while times.iter().all(|t| !t.is_empty()) && !budget.is_zero() && inconclusive(&times) {
+        let started = Instant::now();
+        let more = schedule::measure(&executables, sampling.iterations, 0);
+        if more.iter().any(Vec::is_empty) {
+            return GateMeasurement::Unavailable;
+        }
+        for (samples, extra) in times[timed_from..].iter_mut().zip(more) {
+            samples.extend(extra);
+        }
+        extra_rounds += 1;
+        *budget = budget.saturating_sub(started.elapsed());
+    }
+
+    let subject_times = times.pop().unwrap_or_default();
+    if subject_times.is_empty() || times.iter().any(|t| t.is_empty()) {
+        return GateMeasurement::Unavailable;
+    }
+    GateMeasurement::Measured {
+        baseline_times: times,
+        subject_times,
+        extra_rounds,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(rules: &str) -> GroupDef {
+        toml::from_str(&format!("name = \"Compute\"\nthreshold = 1.0\nrules = {}", rules)).unwrap()
+    }
+
+    fn result(name: &str, low: f64, high: f64) -> GroupResult<'_> {
+        GroupResult {
+            name,
+            ci: Interval {
+                estimate: (low + high) / 2.0,
+                low,
+                high,
+            },
+            threshold: 1.0,
+        }
+    }
+
+    fn evaluate(rules: &str, results: &[GroupResult], unmeasured: &[&str], invalid: &[&str]) -> RuleOutcome {
+        let group = group(rules);
+        group.rules[0].evaluate(&group, results, unmeasured, invalid)
+    }
+
+    #[test]
+    fn verdict_boundaries_are_inclusive_of_the_limit() {
+        let ci = |low, high| Interval {
+            estimate: (low + high) / 2.0,
+            low,
+            high,
+        };
+        assert_eq!(Verdict::of(&ci(0.9, 1.0), 1.0), Verdict::Pass);
+        assert_eq!(Verdict::of(&ci(1.0, 1.1), 1.0), Verdict::Inconclusive);
+        assert_eq!(Verdict::of(&ci(0.95, 1.05), 1.0), Verdict::Inconclusive);
+        assert_eq!(Verdict::of(&ci(1.01, 1.1), 1.0), Verdict::Fail);
+    }
+
+    #[test]
+    fn rules_parse_from_toml() {
+        let group = group(r#"["all", { at-least = 3 }, { geomean-below = 0.95 }, { none-worse-than = 1.1 }]"#);
+        assert_eq!(
+            group.rules,
+            vec![Rule::All, Rule::AtLeast(3), Rule::GeomeanBelow(0.95), Rule::NoneWorseThan(1.1)]
+        );
+        assert_eq!(group.rules[1].requirement(&group), "at least 3 within 1.00x");
+    }
+
+    #[test]
+    fn all_decides_on_both_ends_of_the_intervals() {
+        let within = [result("a", 0.9, 0.95), result("b", 0.8, 1.0)];
+        assert_eq!(evaluate(r#"["all"]"#, &within, &[], &[]).verdict, Verdict::Pass);
+
+        let straddling = [result("a", 0.9, 0.95), result("b", 0.98, 1.02)];
+        let outcome = evaluate(r#"["all"]"#, &straddling, &[], &[]);
+        assert_eq!(outcome.verdict, Verdict::Inconclusive);
+        assert_eq!(outcome.observed, "1 of 2, 1 inconclusive");
+
+        let beyond = [result("a", 0.9, 0.95), result("b", 1.05, 1.1)];
+        assert_eq!(evaluate(r#"["all"]"#, &beyond, &[], &[]).verdict, Verdict::Fail);
+    }
+
+    #[test]
+    fn all_fails_on_unmeasured_benchmarks() {
+        let within = [result("a", 0.9, 0.95)];
+        let outcome = evaluate(r#"["all"]"#, &within, &["b"], &[]);
+        assert_eq!(outcome.verdict, Verdict::Fail);
+        assert_eq!(outcome.observed, "1 of 2, not measured: b");
+    }
+
+    #[test]
+    fn at_least_counts_the_whole_group() {
+        let within = [result("a", 0.9, 0.95), result("b", 0.9, 0.95)];
+        assert_eq!(evaluate(r#"[{ at-least = 2 }]"#, &within, &[], &[]).verdict, Verdict::Pass);
+
+        let outcome = evaluate(r#"[{ at-least = 3 }]"#, &within, &["c"], &["d"]);
+        assert_eq!(outcome.verdict, Verdict::Fail);
+        assert_eq!(outcome.observed, "2 of 4, not measured: c, failed validation: d");
+
+        let straddling = [result("a", 0.9, 0.95), result("b", 0.98, 1.02)];
+        assert_eq!(evaluate(r#"[{ at-least = 2 }]"#, &straddling, &[], &[]).verdict, Verdict::Inconclusive);
+    }
+
+    #[test]
+    fn geomean_needs_every_ratio() {
+        let results = [result("a", 0.8, 0.9), result("b", 0.9, 1.0)];
+        assert_eq!(evaluate(r#"[{ geomean-below = 1.0 }]"#, &results, &[], &[]).verdict, Verdict::Pass);
+        assert_eq!(evaluate(r#"[{ geomean-below = 0.9 }]"#, &results, &[], &[]).verdict, Verdict::Inconclusive);
+        assert_eq!(evaluate(r#"[{ geomean-below = 0.8 }]"#, &results, &[], &[]).verdict, Verdict::Fail);
+        assert_eq!(evaluate(r#"[{ geomean-below = 1.0 }]"#, &results, &["c"], &[]).verdict, Verdict::Fail);
+        assert_eq!(evaluate(r#"[{ geomean-below = 1.0 }]"#, &results, &[], &["c"]).verdict, Verdict::Fail);
+        assert_eq!(evaluate(r#"[{ geomean-below = 1.0 }]"#, &[], &[], &[]).verdict, Verdict::Fail);
+    }
+
+    #[test]
+    fn none_worse_than_fails_on_invalid_benchmarks() {
+        let results = [result("a", 0.9, 1.05), result("b", 1.0, 1.08)];
+        assert_eq!(evaluate(r#"[{ none-worse-than = 1.1 }]"#, &results, &[], &[]).verdict, Verdict::Pass);
+        assert_eq!(evaluate(r#"[{ none-worse-than = 1.06 }]"#, &results, &[], &[]).verdict, Verdict::Inconclusive);
+        assert_eq!(evaluate(r#"[{ none-worse-than = 1.1 }]"#, &results, &[], &["c"]).verdict, Verdict::Fail);
+    }
+}
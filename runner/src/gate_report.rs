@@ -0,0 +1,237 @@
+//! Machine-readable gate results (`gate --format json|junit`).
+//!
+//! Every benchmark of the gate is one test case carrying its ratio,
+//! confidence interval, threshold, raw samples and verdict; every rule is a
+//! test case too, so a dashboard shows why a gate failed, not just that it
+//! did.
+
+use crate::gate::{GateCase, GateDef, GateOutcome, Pinned, RuleOutcome, Verdict};
+use crate::host::HostInfo;
+use crate::report::escape;
+use crate::results::RunDocument;
+use serde::Serialize;
+use std::fmt::Write as _;
+
+/// Formats accepted by `gate --format`.
+pub const FORMATS: [&str; 3] = ["text", "json", "junit"];
+
+#[derive(Debug, Serialize)]
+pub struct GateReport<'a> {
+    pub id: &'a str,
+    pub name: &'a str,
+    pub description: &'a str,
+    pub baselines: &'a [String],
+    pub subject: &'a str,
+    /// Result file a regression gate compared against
+    pub pinned_run: Option<String>,
+    pub timestamp: &'a str,
+    pub git_revision: Option<&'a str>,
+    pub host: Option<&'a HostInfo>,
+    pub confidence: f64,
+    pub verdict: Verdict,
+    pub rules: &'a [RuleOutcome],
+    pub cases: &'a [GateCase],
+}
+
+impl<'a> GateReport<'a> {
+    pub fn new(
+        gate: &'a GateDef,
+        outcome: &'a GateOutcome,
+        doc: &'a RunDocument,
+        pinned: Option<&Pinned>,
+        confidence: f64,
+    ) -> Self {
+        GateReport {
+            id: &gate.id,
+            name: &gate.name,
+            description: &gate.description,
+            baselines: &gate.baselines,
+            subject: &gate.subject,
+            pinned_run: pinned.map(|run| run.source.display().to_string()),
+            timestamp: &doc.timestamp,
+            git_revision: doc.git_revision.as_deref(),
+            host: doc.host.as_ref(),
+            confidence,
+            verdict: outcome.verdict,
+            rules: &outcome.rules,
+            cases: &outcome.cases,
+        }
+    }
+
+    /// Groups in gate order.
+    fn groups(&self) -> Vec<&str> {
+        let mut groups: Vec<&str> = Vec::new();
+        for group in self.cases.iter().map(|c| c.group.as_str()).chain(self.rules.iter().map(|r| r.group.as_str())) {
+            if !groups.contains(&group) {
+                groups.push(group);
+            }
+        }
+        groups
+    }
+}
+
+pub fn render_json(report: &GateReport) -> String {
+    serde_json::to_string_pretty(report).unwrap_or_default()
+}
+
+/// Tally of JUnit outcomes
+#[derive(Default)]
+struct Counts {
+    tests: usize,
+    failures: usize,
+    errors: usize,
+    seconds: f64,
+}
+
+impl Counts {
+    fn add(&mut self, verdict: Option<Verdict>, seconds: f64) {
+        self.tests += 1;
+        self.seconds += seconds;
+        match verdict {
+            Some(Verdict::Pass) => {}
+            // Unmeasured benchmarks count as over their limit in the rules
+            Some(Verdict::Fail) | None => self.failures += 1,
+            Some(Verdict::Inconclusive) => self.errors += 1,
+        }
+    }
+
+    fn attributes(&self) -> String {
+        format!(
+            "tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\"",
+            self.tests, self.failures, self.errors, self.seconds
+        )
+    }
+}
+
+/// JUnit XML with one test suite per gate group. Failed, invalid and
+/// unbuildable benchmarks are failures, inconclusive ones errors.
+pub fn render_junit(report: &GateReport) -> String {
+    let mut total = Counts::default();
+    let mut suites = String::new();
+
+    for group in report.groups() {
+        let classname = format!("gate.{}.{}", report.id, group.to_lowercase().replace(' ', "_"));
+        let mut counts = Counts::default();
+        let mut cases = String::new();
+
+        for case in report.cases.iter().filter(|c| c.group == group) {
+            let seconds = case.baseline_samples_ms.iter().chain(&case.subject_samples_ms).sum::<f64>() / 1000.0;
+            counts.add(case.verdict, seconds);
+            let _ = writeln!(
+                cases,
+                "    <testcase classname=\"{}\" name=\"{}\" time=\"{:.3}\">",
+                escape(&classname),
+                escape(&case.benchmark),
+                seconds
+            );
+            if let Some(element) = case_outcome(report, case) {
+                let _ = writeln!(cases, "      {}", element);
+            }
+            let _ = writeln!(cases, "      <system-out>{}</system-out>", escape(&case_details(report, case)));
+            let _ = writeln!(cases, "    </testcase>");
+        }
+
+        for rule in report.rules.iter().filter(|r| r.group == group) {
+            counts.add(Some(rule.verdict), 0.0);
+            let name = format!("rule: {}", rule.requirement);
+            let message = escape(&rule.observed);
+            let _ = match rule.verdict {
+                Verdict::Pass => writeln!(
+                    cases,
+                    "    <testcase classname=\"{}\" name=\"{}\" time=\"0\"/>",
+                    escape(&classname),
+                    escape(&name)
+                ),
+                Verdict::Fail => writeln!(
+                    cases,
+                    "    <testcase classname=\"{}\" name=\"{}\" time=\"0\">\n      <failure type=\"rule\" message=\"{}\"/>\n    </testcase>",
+                    escape(&classname),
+                    escape(&name),
+                    message
+                ),
+                Verdict::Inconclusive => writeln!(
+                    cases,
+                    "    <testcase classname=\"{}\" name=\"{}\" time=\"0\">\n      <error type=\"inconclusive\" message=\"{}\"/>\n    </testcase>",
+                    escape(&classname),
+                    escape(&name),
+                    message
+                ),
+            };
+        }
+
+        let _ = writeln!(
+            suites,
+            "  <testsuite name=\"{}\" timestamp=\"{}\" {}>",
+            escape(&format!("{}: {}", report.name, group)),
+            escape(report.timestamp),
+            counts.attributes()
+        );
+        suites.push_str(&cases);
+        let _ = writeln!(suites, "  </testsuite>");
+
+        total.tests += counts.tests;
+        total.failures += counts.failures;
+        total.errors += counts.errors;
+        total.seconds += counts.seconds;
+    }
+
+    let mut out = String::new();
+    let _ = writeln!(out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+    let _ = writeln!(out, "<testsuites name=\"{}\" {}>", escape(report.name), total.attributes());
+    out.push_str(&suites);
+    let _ = writeln!(out, "</testsuites>");
+    out
+}
+
+/// `<failure>` or `<error>` element of a benchmark case.
+fn case_outcome(report: &GateReport, case: &GateCase) -> Option<String> {
+    let interval = match (case.ratio, case.ci_low, case.ci_high) {
+        (Some(ratio), Some(low), Some(high)) => format!(
+            "ratio {:.3}x, {:.0}% CI [{:.3}, {:.3}]",
+            ratio,
+            report.confidence * 100.0,
+            low,
+            high
+        ),
+        _ => String::new(),
+    };
+
+    match (case.verdict, &case.error) {
+        (Some(Verdict::Pass), _) => None,
+        (Some(Verdict::Fail), Some(error)) => Some(format!("<failure type=\"invalid\" message=\"{}\"/>", escape(error))),
+        (Some(Verdict::Fail), None) => Some(format!(
+            "<failure type=\"threshold\" message=\"{} above limit {:.2}x\"/>",
+            interval, case.threshold
+        )),
+        (Some(Verdict::Inconclusive), _) => Some(format!(
+            "<error type=\"inconclusive\" message=\"{} straddles limit {:.2}x\"/>",
+            interval, case.threshold
+        )),
+        (None, error) => Some(format!(
+            "<failure type=\"unmeasured\" message=\"{}\"/>",
+            escape(error.as_deref().unwrap_or("not measured"))
+        )),
+    }
+}
+
+fn case_details(report: &GateReport, case: &GateCase) -> String {
+    let samples = |times: &[f64]| times.iter().map(|t| format!("{:.3}", t)).collect::<Vec<_>>().join(" ");
+    let mut details = format!("category={} threshold={:.2}", case.category, case.threshold);
+    if let (Some(ratio), Some(low), Some(high)) = (case.ratio, case.ci_low, case.ci_high) {
+        let _ = write!(details, " ratio={:.4} ci_low={:.4} ci_high={:.4}", ratio, low, high);
+    }
+    if let Some(error) = &case.error {
+        let _ = write!(details, "\nerror: {}", error);
+    }
+    if !case.baseline_samples_ms.is_empty() {
+        let _ = write!(
+            details,
+            "\n{} samples (ms): {}\n{} samples (ms): {}",
+            case.baseline.as_deref().unwrap_or("baseline"),
+            samples(&case.baseline_samples_ms),
+            report.subject,
+            samples(&case.subject_samples_ms)
+        );
+    }
+    details
+}
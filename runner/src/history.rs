@@ -0,0 +1,408 @@
+//! Append-only history of benchmark runs.
+//!
+//! `results/history.jsonl` holds one [`HistoryRecord`] per line. A record
+//! is appended for every saved run or gate and is never rewritten, so the
+//! file can be kept in git and merged by concatenation. Records carry only
+//! the per-language summary; the raw samples stay in the run document named
+//! by `source`.
+
+use crate::results::{self, GateRecord, RunDocument};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub const HISTORY_FILE: &str = "history.jsonl";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HistoryRecord {
+    pub timestamp: String,
+    /// "run", "gate" or "import"
+    pub kind: String,
+    #[serde(default)]
+    pub session: Option<String>,
+    #[serde(default)]
+    pub git_revision: Option<String>,
+    #[serde(default)]
+    pub bmb_version: Option<String>,
+    /// Result file the record was taken from
+    #[serde(default)]
+    pub source: Option<String>,
+    pub samples: Vec<Sample>,
+    #[serde(default)]
+    pub gate: Option<GateRecord>,
+}
+
+/// Summary of one benchmark in one language.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Sample {
+    pub benchmark: String,
+    pub category: String,
+    pub language: String,
+    pub median_ms: f64,
+    #[serde(default)]
+    pub iterations: u32,
+}
+
+impl HistoryRecord {
+    pub fn from_run(doc: &RunDocument, source: &Path) -> Self {
+        HistoryRecord {
+            timestamp: doc.timestamp.clone(),
+            kind: doc.kind.clone(),
+            session: doc.session.clone(),
+            git_revision: doc.git_revision.clone(),
+            bmb_version: doc.toolchains.get("bmb").cloned(),
+            source: Some(source.display().to_string()),
+            samples: doc
+                .reports
+                .iter()
+                .flat_map(|r| &r.results)
+                .map(|r| Sample {
+                    benchmark: r.name.clone(),
+                    category: r.category.clone(),
+                    language: r.language.clone(),
+                    median_ms: r.median_ms,
+                    iterations: r.iterations,
+                })
+                .collect(),
+            gate: doc.gate.clone(),
+        }
+    }
+
+    fn median(&self, benchmark: &str, language: &str) -> Option<f64> {
+        self.samples
+            .iter()
+            .find(|s| s.benchmark == benchmark && s.language == language)
+            .map(|s| s.median_ms)
+    }
+
+    fn date(&self) -> String {
+        // Imported records only know the day
+        let format = if self.timestamp.len() == 10 { "%Y-%m-%d" } else { "%Y-%m-%d %H:%M" };
+        sort_key(&self.timestamp).format(format).to_string()
+    }
+}
+
+pub fn history_path(results_dir: &Path) -> PathBuf {
+    results_dir.join(HISTORY_FILE)
+}
+
+pub fn append(results_dir: &Path, records: &[HistoryRecord]) -> Result<(), String> {
+    let path = history_path(results_dir);
+    let mut lines = String::new();
+    for record in records {
+        lines.push_str(&serde_json::to_string(record).map_err(|e| e.to_string())?);
+        lines.push('\n');
+    }
+
+    fs::create_dir_all(results_dir)
+        .and_then(|_| OpenOptions::new().create(true).append(true).open(&path))
+        .and_then(|mut file| file.write_all(lines.as_bytes()))
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// All records, oldest first. A missing file is an empty history.
+pub fn load(results_dir: &Path) -> Result<Vec<HistoryRecord>, String> {
+    let path = history_path(results_dir);
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("{}: {}", path.display(), e)),
+    };
+
+    let mut records = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = serde_json::from_str(line)
+            .map_err(|e| format!("{}:{}: {}", path.display(), n + 1, e))?;
+        records.push(record);
+    }
+
+    // Imports append old records late; order by when they were measured
+    records.sort_by_key(|r: &HistoryRecord| sort_key(&r.timestamp));
+    Ok(records)
+}
+
+/// Run pinned by `selector`: a result file (`*.json`), or the run of the
+/// latest measured record whose session, git revision (or its prefix) or
+/// BMB version matches. Versions match with or without the leading `v` and
+/// by release series, so `v0.35` pins the latest 0.35.x run.
+pub fn find_run(results_dir: &Path, selector: &str) -> Result<(PathBuf, RunDocument), String> {
+    let as_path = Path::new(selector);
+    if as_path.extension().is_some_and(|ext| ext == "json") {
+        return results::load_run(as_path).map(|doc| (as_path.to_path_buf(), doc));
+    }
+
+    let records = load(results_dir)?;
+    let record = records
+        .iter()
+        .rev()
+        .filter(|r| r.kind != "import")
+        .find(|r| {
+            r.session.as_deref() == Some(selector)
+                || r.bmb_version.as_deref().is_some_and(|v| version_matches(v, selector))
+                || r.git_revision.as_deref().is_some_and(|rev| rev.starts_with(selector))
+        })
+        .ok_or_else(|| {
+            format!(
+                "no measured run matches '{}' in {}",
+                selector,
+                history_path(results_dir).display()
+            )
+        })?;
+
+    // Sources are recorded relative to where the runner was started
+    let source = record.source.as_deref().unwrap_or_default();
+    let path = [
+        PathBuf::from(source),
+        results_dir.join(Path::new(source).file_name().unwrap_or_default()),
+    ]
+    .into_iter()
+    .find(|p| p.is_file())
+    .ok_or_else(|| format!("result file '{}' of run '{}' not found", source, selector))?;
+
+    results::load_run(&path).map(|doc| (path, doc))
+}
+
+/// Timestamps are RFC 3339 for measured runs but may be bare dates for
+/// imported ones.
+fn sort_key(timestamp: &str) -> NaiveDateTime {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| t.naive_local())
+        .or_else(|_| NaiveDate::parse_from_str(timestamp, "%Y-%m-%d").map(|d| d.and_hms_opt(0, 0, 0).unwrap()))
+        .unwrap_or_default()
+}
+
+pub struct Query<'a> {
+    pub benchmark: Option<&'a str>,
+    pub language: Option<&'a str>,
+    pub session: Option<&'a str>,
+    pub limit: usize,
+}
+
+pub fn show(records: &[HistoryRecord], query: &Query) {
+    let records: Vec<&HistoryRecord> = records
+        .iter()
+        .filter(|r| query.session.is_none_or(|s| r.session.as_deref() == Some(s)))
+        .collect();
+
+    println!("{}", "=== BMB Benchmark History ===".cyan().bold());
+    println!();
+
+    match query.benchmark {
+        Some(benchmark) => show_trend(&records, benchmark, query),
+        None => show_runs(&records, query.limit),
+    }
+
+    println!();
+    show_gate_milestones(&records);
+}
+
+fn show_runs(records: &[&HistoryRecord], limit: usize) {
+    println!(
+        "{:<17} {:<7} {:<12} {:<16} {:>11} {:<12}",
+        "Date", "Kind", "Session", "Revision", "Benchmarks", "Gate"
+    );
+    println!("{}", "-".repeat(80));
+
+    let start = records.len().saturating_sub(limit);
+    for record in &records[start..] {
+        let mut benchmarks: Vec<&str> = record.samples.iter().map(|s| s.benchmark.as_str()).collect();
+        benchmarks.sort();
+        benchmarks.dedup();
+
+        let gate = match &record.gate {
+            Some(g) if g.passed => format!("{} {}", g.name, "PASS".green()),
+            Some(g) if g.inconclusive => format!("{} {}", g.name, "INCONCLUSIVE".yellow()),
+            Some(g) => format!("{} {}", g.name, "FAIL".red()),
+            None => String::new(),
+        };
+        println!(
+            "{:<17} {:<7} {:<12} {:<16} {:>11} {}",
+            record.date(),
+            record.kind,
+            record.session.as_deref().unwrap_or("-"),
+            record.git_revision.as_deref().unwrap_or("-"),
+            benchmarks.len(),
+            gate
+        );
+    }
+
+    if records.is_empty() {
+        println!("{}", "No runs recorded.".yellow());
+    }
+}
+
+fn show_trend(records: &[&HistoryRecord], benchmark: &str, query: &Query) {
+    let records: Vec<&HistoryRecord> = records
+        .iter()
+        .copied()
+        .filter(|r| r.samples.iter().any(|s| s.benchmark == benchmark))
+        .collect();
+    if records.is_empty() {
+        println!("{} No history for '{}'", "Error:".red(), benchmark);
+        return;
+    }
+
+    let mut languages: Vec<&str> = Vec::new();
+    for sample in records.iter().flat_map(|r| &r.samples) {
+        if sample.benchmark == benchmark
+            && query.language.is_none_or(|l| l == sample.language)
+            && !languages.contains(&sample.language.as_str())
+        {
+            languages.push(&sample.language);
+        }
+    }
+    languages.sort_by_key(|l| (*l != "c", *l));
+    let show_ratio = query.language.is_none() && languages.contains(&"c") && languages.contains(&"bmb");
+
+    println!("{} {}", "Trend:".green(), benchmark);
+    print!("{:<17} {:<12} {:<16} {:<12}", "Date", "Session", "Revision", "BMB version");
+    for lang in &languages {
+        print!(" {:>11}", format!("{} (ms)", lang.to_uppercase()));
+    }
+    if show_ratio {
+        print!(" {:>8}", "BMB/C");
+    }
+    println!();
+    println!("{}", "-".repeat(60 + 12 * languages.len() + if show_ratio { 9 } else { 0 }));
+
+    let start = records.len().saturating_sub(query.limit);
+    for record in &records[start..] {
+        print!(
+            "{:<17} {:<12} {:<16} {:<12}",
+            record.date(),
+            record.session.as_deref().unwrap_or("-"),
+            record.git_revision.as_deref().unwrap_or("-"),
+            short_version(record.bmb_version.as_deref())
+        );
+        for lang in &languages {
+            match record.median(benchmark, lang) {
+                Some(ms) => print!(" {:>11.2}", ms),
+                None => print!(" {:>11}", "-"),
+            }
+        }
+        if show_ratio {
+            match (record.median(benchmark, "bmb"), record.median(benchmark, "c")) {
+                (Some(bmb), Some(c)) => print!(" {:>7.2}x", bmb / c),
+                _ => print!(" {:>8}", "-"),
+            }
+        }
+        println!();
+    }
+
+    // Overall change per language across the whole history
+    println!();
+    for lang in &languages {
+        let series: Vec<f64> = records.iter().filter_map(|r| r.median(benchmark, lang)).collect();
+        if let (Some(first), Some(last)) = (series.first(), series.last()) {
+            if series.len() < 2 {
+                continue;
+            }
+            let change = (last / first - 1.0) * 100.0;
+            let text = format!("{:+.1}%", change);
+            println!(
+                "  {:<6} {:.2} ms -> {:.2} ms over {} runs ({})",
+                lang.to_uppercase(),
+                first,
+                last,
+                series.len(),
+                if change <= 0.0 { text.green() } else { text.red() }
+            );
+        }
+    }
+}
+
+/// First passing run of every gate ever recorded.
+fn show_gate_milestones(records: &[&HistoryRecord]) {
+    let mut gates: BTreeMap<&str, (Option<&HistoryRecord>, &HistoryRecord)> = BTreeMap::new();
+    for record in records {
+        let Some(gate) = &record.gate else {
+            continue;
+        };
+        let entry = gates.entry(&gate.name).or_insert((None, record));
+        entry.1 = record;
+        if gate.passed && entry.0.is_none() {
+            entry.0 = Some(record);
+        }
+    }
+
+    if gates.is_empty() {
+        return;
+    }
+
+    println!("{}", "Gate milestones:".cyan());
+    for (name, (first_pass, last)) in gates {
+        match first_pass {
+            Some(record) => println!(
+                "  {:<12} first passed {} (session {}, revision {})",
+                name,
+                record.date().green(),
+                record.session.as_deref().unwrap_or("-"),
+                record.git_revision.as_deref().unwrap_or("-")
+            ),
+            None => println!(
+                "  {:<12} {} (last attempt {})",
+                name,
+                "never passed".red(),
+                last.date()
+            ),
+        }
+    }
+}
+
+/// "bmb v0.34.2 (abc123)" -> "0.34.2"
+fn short_version(version: Option<&str>) -> String {
+    version
+        .and_then(|v| {
+            v.split_whitespace()
+                .map(|w| w.trim_start_matches('v'))
+                .find(|w| w.starts_with(|c: char| c.is_ascii_digit()))
+        })
+        .unwrap_or("-")
+        .to_string()
+}
+
+/// Whether `bmb --version` output is the version `selector` names:
+/// "v0.35" and "0.35" match "bmb v0.35.1 (abc123)", "v0.3" does not.
+fn version_matches(version: &str, selector: &str) -> bool {
+    let short = short_version(Some(version));
+    let wanted = selector.trim_start_matches('v');
+    version == selector || short == wanted || short.starts_with(&format!("{}.", wanted))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_version_is_the_first_numeric_word() {
+        assert_eq!(short_version(Some("bmb v0.35.1 (abc123)")), "0.35.1");
+        assert_eq!(short_version(Some("0.34")), "0.34");
+        assert_eq!(short_version(Some("bmb dev")), "-");
+        assert_eq!(short_version(None), "-");
+    }
+
+    #[test]
+    fn imported_dates_sort_with_measured_timestamps() {
+        let mut timestamps = ["2026-01-09T08:00:00+00:00", "2026-01-09", "2026-01-08T23:59:00+00:00"];
+        timestamps.sort_by_key(|t| sort_key(t));
+        assert_eq!(timestamps, ["2026-01-08T23:59:00+00:00", "2026-01-09", "2026-01-09T08:00:00+00:00"]);
+    }
+
+    #[test]
+    fn versions_match_exactly_or_by_release_series() {
+        let version = "bmb v0.35.1 (abc123)";
+        for selector in ["bmb v0.35.1 (abc123)", "v0.35.1", "0.35.1", "v0.35", "0.35", "0"] {
+            assert!(version_matches(version, selector), "{} did not match", selector);
+        }
+        for selector in ["v0.3", "0.35.10", "0.36", "v0.35.1.2", "abc123"] {
+            assert!(!version_matches(version, selector), "{} matched", selector);
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! Description of the machine and toolchains a run was measured on.
+//!
+//! Everything is best effort: a value that cannot be read on this platform
+//! is left empty rather than failing the run.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::process::Command;
+
+/// Environment variable naming the BMB runtime archive linked into native
+/// builds.
+pub const RUNTIME_ENV: &str = "BMB_RUNTIME_PATH";
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct HostInfo {
+    pub hostname: String,
+    /// Distribution or OS name, e.g. "Debian GNU/Linux 12 (bookworm)"
+    pub os: String,
+    pub kernel: String,
+    /// Running under Windows Subsystem for Linux
+    #[serde(default)]
+    pub wsl: bool,
+    pub cpu_model: String,
+    pub logical_cores: usize,
+    /// cpufreq scaling governor of cpu0, where exposed
+    #[serde(default)]
+    pub governor: Option<String>,
+    pub memory_mb: u64,
+}
+
+impl HostInfo {
+    pub fn detect() -> Self {
+        let kernel = read_trimmed("/proc/sys/kernel/osrelease")
+            .or_else(|| command_line("uname", &["-r"]))
+            .unwrap_or_default();
+
+        HostInfo {
+            hostname: read_trimmed("/proc/sys/kernel/hostname")
+                .or_else(|| std::env::var("COMPUTERNAME").ok())
+                .or_else(|| command_line("hostname", &[]))
+                .unwrap_or_default(),
+            os: os_name(),
+            wsl: kernel.to_lowercase().contains("microsoft"),
+            kernel,
+            cpu_model: proc_field("/proc/cpuinfo", "model name")
+                .or_else(|| std::env::var("PROCESSOR_IDENTIFIER").ok())
+                .unwrap_or_default(),
+            logical_cores: std::thread::available_parallelism().map(|n| n.get()).unwrap_or(0),
+            governor: read_trimmed("/sys/devices/system/cpu/cpu0/cpufreq/scaling_governor"),
+            memory_mb: proc_field("/proc/meminfo", "MemTotal")
+                .and_then(|v| v.split_whitespace().next()?.parse::<u64>().ok())
+                .map(|kb| kb / 1024)
+                .unwrap_or(0),
+        }
+    }
+
+    /// One-line summary for report headers.
+    pub fn summary(&self) -> String {
+        let mut parts = vec![self.hostname.clone()];
+        if !self.cpu_model.is_empty() {
+            parts.push(format!("{} ({} threads)", self.cpu_model, self.logical_cores));
+        }
+        if self.memory_mb > 0 {
+            parts.push(format!("{:.1} GiB", self.memory_mb as f64 / 1024.0));
+        }
+        let mut os = format!("{} {}", self.os, self.kernel).trim().to_string();
+        if self.wsl {
+            os.push_str(" (WSL)");
+        }
+        parts.push(os);
+        if let Some(governor) = &self.governor {
+            parts.push(format!("governor {}", governor));
+        }
+        parts.retain(|p| !p.is_empty());
+        parts.join(", ")
+    }
+}
+
+/// Tool name -> first line of its `--version` output, for every tool that
+/// is installed, plus the FNV-1a hash of the BMB runtime archive when
+/// `BMB_RUNTIME_PATH` points at one.
+pub fn toolchain_versions() -> BTreeMap<String, String> {
+    let mut versions = BTreeMap::new();
+    for tool in ["gcc", "clang", "rustc", "bmb"] {
+        if let Some(line) = command_line(tool, &["--version"]) {
+            versions.insert(tool.to_string(), line);
+        }
+    }
+
+    if let Ok(path) = std::env::var(RUNTIME_ENV) {
+        if let Ok(bytes) = fs::read(&path) {
+            let hash = bytes
+                .iter()
+                .fold(0xcbf2_9ce4_8422_2325u64, |h, b| (h ^ *b as u64).wrapping_mul(0x0100_0000_01b3));
+            versions.insert("bmb-runtime".to_string(), format!("{} fnv1a:{:016x}", path, hash));
+        }
+    }
+    versions
+}
+
+/// First line of a command's stdout, if it ran successfully.
+fn command_line(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    text.lines().next().map(|l| l.trim().to_string()).filter(|l| !l.is_empty())
+}
+
+fn read_trimmed(path: &str) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Value of the first `key: value` line in a /proc file.
+fn proc_field(path: &str, key: &str) -> Option<String> {
+    fs::read_to_string(path).ok()?.lines().find_map(|line| {
+        let (k, v) = line.split_once(':')?;
+        (k.trim() == key).then(|| v.trim().to_string())
+    })
+}
+
+fn os_name() -> String {
+    fs::read_to_string("/etc/os-release")
+        .ok()
+        .and_then(|text| {
+            text.lines()
+                .find_map(|l| l.strip_prefix("PRETTY_NAME="))
+                .map(|v| v.trim_matches('"').to_string())
+        })
+        .unwrap_or_else(|| std::env::consts::OS.to_string())
+}
@@ -0,0 +1,529 @@
+//! Import of hand-written results into the history store.
+//!
+//! Before the runner saved JSON, results were recorded as markdown reports
+//! (`results/<date>_<phase>.md`) and `benchmark_results.csv`. Markdown
+//! tables are read when their first column is a benchmark name and other
+//! columns are `<Lang> (ms)` or `Median (ms)`, or when their first column
+//! is a language and other columns are benchmark names. Gate verdicts are
+//! taken from lines such as `### Gate 1: Compute Parity ✅ PASSED`.
+//! Only names of benchmarks that exist under `benches/` are imported;
+//! rows with timings under any other name are reported as unmatched.
+//!
+//! Per-benchmark `Language | Time (ms)` tables (as in the f64 intrinsics
+//! report) are not supported: their rows mix interpreter and native runs
+//! of different programs, so they are reported as skipped rather than
+//! imported.
+
+use crate::history::{HistoryRecord, Sample};
+use crate::results::GateRecord;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+const LANGUAGES: [&str; 3] = ["c", "rust", "bmb"];
+
+/// Values given on the command line win over anything found in the file.
+#[derive(Default)]
+pub struct Overrides {
+    pub date: Option<String>,
+    pub bmb_version: Option<String>,
+    pub session: Option<String>,
+}
+
+/// Result of importing one file.
+pub struct Imported {
+    pub records: Vec<HistoryRecord>,
+    /// Tables that were recognized but not imported
+    pub skipped_tables: usize,
+    /// Names with timings that match no benchmark under `benches/`
+    pub unmatched: Vec<String>,
+}
+
+/// `--date` value: a calendar date in YYYY-MM-DD form.
+pub fn parse_date(text: &str) -> Result<String, String> {
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .map_err(|_| format!("'{}' is not a YYYY-MM-DD date", text))
+}
+
+/// Parse one legacy result file. `known` maps benchmark name to category.
+pub fn import_file(
+    path: &Path,
+    known: &BTreeMap<String, String>,
+    overrides: &Overrides,
+) -> Result<Imported, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let is_csv = path.extension().is_some_and(|e| e == "csv");
+
+    let header_line = |label: &str| text.lines().find(|l| l.contains(label) && !l.trim_start().starts_with('|'));
+
+    // The modification time is the checkout time, which would make an old
+    // result the newest in the history, so a file without a date needs one
+    let date = overrides
+        .date
+        .clone()
+        .or_else(|| header_line("Date").and_then(find_date))
+        .or_else(|| find_date(&file_name))
+        .ok_or_else(|| format!("{}: no date found; pass --date YYYY-MM-DD", path.display()))?;
+
+    let bmb_version = overrides.bmb_version.clone().or_else(|| {
+        header_line("BMB Version")
+            .or_else(|| header_line("**Version**"))
+            .and_then(find_version)
+            .or_else(|| text.lines().next().and_then(find_version))
+            .or_else(|| find_version(&file_name))
+    });
+
+    let session = overrides
+        .session
+        .clone()
+        .or_else(|| find_phase(&file_name))
+        .or_else(|| bmb_version.clone());
+
+    let mut unmatched = Vec::new();
+    let samples = if is_csv {
+        csv_samples(&text, known, &mut unmatched)
+    } else {
+        markdown_samples(&text, known, median_language(&text), &mut unmatched)
+    };
+
+    let record = |samples: Vec<Sample>, gate: Option<GateRecord>| HistoryRecord {
+        timestamp: date.clone(),
+        kind: "import".to_string(),
+        session: session.clone(),
+        git_revision: None,
+        bmb_version: bmb_version.clone(),
+        source: Some(file_name.clone()),
+        samples,
+        gate,
+    };
+
+    let mut records = Vec::new();
+    if !samples.is_empty() {
+        records.push(record(samples, None));
+    }
+    for (name, passed) in gate_verdicts(&text) {
+        records.push(record(
+            Vec::new(),
+            Some(GateRecord {
+                name,
+                passed,
+                inconclusive: false,
+                passed_count: 0,
+                failed_count: 0,
+                inconclusive_count: 0,
+                excluded_count: 0,
+            }),
+        ));
+    }
+
+    Ok(Imported {
+        records,
+        skipped_tables: if is_csv { 0 } else { unsupported_tables(&text) },
+        unmatched,
+    })
+}
+
+/// `benchmark,c_time_s,bmb_time_s,...`: every `<lang>_time_s` or
+/// `<lang>_time_ms` column is one language.
+fn csv_samples(text: &str, known: &BTreeMap<String, String>, unmatched: &mut Vec<String>) -> Vec<Sample> {
+    let mut lines = text.lines();
+    let Some(header) = lines.next() else {
+        return Vec::new();
+    };
+
+    let columns: Vec<(usize, &str, f64)> = header
+        .split(',')
+        .enumerate()
+        .filter_map(|(i, h)| {
+            let h = h.trim();
+            let (lang, scale) = match (h.strip_suffix("_time_s"), h.strip_suffix("_time_ms")) {
+                (Some(lang), _) => (lang, 1000.0),
+                (_, Some(lang)) => (lang, 1.0),
+                _ => return None,
+            };
+            LANGUAGES.contains(&lang).then_some((i, lang, scale))
+        })
+        .collect();
+
+    let mut samples = Vec::new();
+    for line in lines {
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let Some((benchmark, category)) = fields.first().and_then(|f| lookup(known, f)) else {
+            if columns.iter().any(|&(i, _, _)| fields.get(i).and_then(|f| parse_number(f)).is_some()) {
+                note_unmatched(unmatched, fields.first().copied().unwrap_or_default());
+            }
+            continue;
+        };
+        for &(i, lang, scale) in &columns {
+            if let Some(value) = fields.get(i).and_then(|f| parse_number(f)) {
+                push_sample(&mut samples, benchmark, category, lang, value * scale);
+            }
+        }
+    }
+    samples
+}
+
+fn markdown_samples(
+    text: &str,
+    known: &BTreeMap<String, String>,
+    median_lang: &str,
+    unmatched: &mut Vec<String>,
+) -> Vec<Sample> {
+    let mut samples = Vec::new();
+
+    for table in markdown_tables(text) {
+        let Some((header, rows)) = table.split_first() else {
+            continue;
+        };
+        let first = header.first().map(|h| h.to_lowercase()).unwrap_or_default();
+
+        if first == "benchmark" {
+            let columns: Vec<(usize, &str)> = header
+                .iter()
+                .enumerate()
+                .filter_map(|(i, h)| header_language(h, median_lang).map(|lang| (i, lang)))
+                .collect();
+
+            for row in rows {
+                let Some((benchmark, category)) = row.first().and_then(|c| lookup(known, c)) else {
+                    if columns.iter().any(|&(i, _)| row.get(i).and_then(|c| parse_number(c)).is_some()) {
+                        note_unmatched(unmatched, row.first().map(String::as_str).unwrap_or_default());
+                    }
+                    continue;
+                };
+                for &(i, lang) in &columns {
+                    if let Some(ms) = row.get(i).and_then(|c| parse_number(c)) {
+                        push_sample(&mut samples, benchmark, category, lang, ms);
+                    }
+                }
+            }
+        } else if first == "language" && !is_unsupported(header) {
+            // Transposed: one row per language, one column per benchmark
+            let columns: Vec<(usize, &str, &str)> = header
+                .iter()
+                .enumerate()
+                .skip(1)
+                .filter_map(|(i, h)| lookup(known, h).map(|(b, c)| (i, b, c)))
+                .collect();
+            for (i, h) in header.iter().enumerate().skip(1) {
+                let timed = rows.iter().any(|row| row.get(i).and_then(|c| parse_number(c)).is_some());
+                if timed && lookup(known, h).is_none() {
+                    note_unmatched(unmatched, h);
+                }
+            }
+
+            for row in rows {
+                let lang = row.first().map(|c| leading_word(c)).unwrap_or_default();
+                let Some(lang) = LANGUAGES.iter().find(|l| **l == lang) else {
+                    continue;
+                };
+                for &(i, benchmark, category) in &columns {
+                    if let Some(ms) = row.get(i).and_then(|c| parse_number(c)) {
+                        push_sample(&mut samples, benchmark, category, lang, ms);
+                    }
+                }
+            }
+        }
+    }
+
+    samples
+}
+
+/// `Language | Time (ms)` tables, which name their benchmark only in the
+/// heading above them.
+fn unsupported_tables(text: &str) -> usize {
+    markdown_tables(text)
+        .iter()
+        .filter_map(|table| table.first())
+        .filter(|header| is_unsupported(header))
+        .count()
+}
+
+fn is_unsupported(header: &[String]) -> bool {
+    header.first().is_some_and(|h| h.eq_ignore_ascii_case("language"))
+        && header.iter().any(|h| h.eq_ignore_ascii_case("time (ms)"))
+}
+
+/// Tables as rows of trimmed cells, with the separator row dropped.
+fn markdown_tables(text: &str) -> Vec<Vec<Vec<String>>> {
+    let mut tables = Vec::new();
+    let mut current: Vec<Vec<String>> = Vec::new();
+
+    for line in text.lines().map(str::trim) {
+        if !line.starts_with('|') {
+            if !current.is_empty() {
+                tables.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        let cells: Vec<String> = line
+            .trim_matches('|')
+            .split('|')
+            .map(|c| c.trim().to_string())
+            .collect();
+        if cells.iter().all(|c| !c.is_empty() && c.chars().all(|ch| matches!(ch, '-' | ':'))) {
+            continue;
+        }
+        current.push(cells);
+    }
+    if !current.is_empty() {
+        tables.push(current);
+    }
+    tables
+}
+
+/// `BMB (ms)` -> bmb; `Median (ms)` -> the file's single measured language.
+fn header_language<'a>(header: &str, median_lang: &'a str) -> Option<&'a str> {
+    let name = header.strip_suffix("(ms)")?.trim().to_lowercase();
+    if name == "median" {
+        return Some(median_lang);
+    }
+    LANGUAGES.iter().find(|l| **l == name).copied()
+}
+
+/// Language of single-column `Median (ms)` tables. Early baselines were
+/// Rust-only because GCC was unavailable.
+fn median_language(text: &str) -> &'static str {
+    let rust_only = text
+        .lines()
+        .any(|l| l.contains("Compiler") && l.contains("rustc"));
+    if rust_only {
+        "rust"
+    } else {
+        "c"
+    }
+}
+
+/// `### Gate 1: Compute Parity ✅ PASSED` -> ("Gate #1", true). PARTIAL,
+/// BLOCKED and FAILED count as not passed; the first verdict per gate wins.
+fn gate_verdicts(text: &str) -> Vec<(String, bool)> {
+    let mut verdicts: Vec<(String, bool)> = Vec::new();
+
+    for line in text.lines() {
+        // "Gate Status: ... Gate #1 PASSED" names the gate second
+        let number = line.match_indices("Gate ").find_map(|(pos, _)| {
+            let rest = line[pos + 5..].trim_start_matches('#');
+            let number: String = rest.chars().take_while(|c| c.is_ascii_digit() || *c == '.').collect();
+            let number = number.trim_end_matches('.').to_string();
+            (!number.is_empty()).then_some(number)
+        });
+        let Some(number) = number else {
+            continue;
+        };
+
+        let passed = if line.contains("PASSED") {
+            true
+        } else if ["FAILED", "PARTIAL", "BLOCKED"].iter().any(|s| line.contains(s)) {
+            false
+        } else {
+            continue;
+        };
+
+        let name = format!("Gate #{}", number);
+        if !verdicts.iter().any(|(n, _)| *n == name) {
+            verdicts.push((name, passed));
+        }
+    }
+    verdicts
+}
+
+/// Match a table cell such as `fibonacci(35)` or `` `json_parse` `` to a
+/// known benchmark.
+fn lookup<'a>(known: &'a BTreeMap<String, String>, cell: &str) -> Option<(&'a str, &'a str)> {
+    let name = leading_word(cell);
+    known.get_key_value(&name).map(|(b, c)| (b.as_str(), c.as_str()))
+}
+
+/// `reverse-complement(1000)` -> `reverse-complement`
+fn leading_word(cell: &str) -> String {
+    cell.trim_matches(|c: char| c == '*' || c == '`' || c.is_whitespace())
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-')
+        .collect::<String>()
+        .trim_end_matches('-')
+        .to_lowercase()
+}
+
+fn note_unmatched(unmatched: &mut Vec<String>, cell: &str) {
+    let name = leading_word(cell);
+    if !name.is_empty() && !unmatched.contains(&name) {
+        unmatched.push(name);
+    }
+}
+
+/// `~57ms`, `**34.52**`, `.023` -> number; `-` and `N/A` -> None.
+fn parse_number(cell: &str) -> Option<f64> {
+    let cleaned = cell
+        .trim_matches(|c: char| c == '*' || c == '~' || c.is_whitespace())
+        .trim_end_matches("ms")
+        .trim();
+    cleaned.parse::<f64>().ok().filter(|v| v.is_finite() && *v > 0.0)
+}
+
+fn push_sample(samples: &mut Vec<Sample>, benchmark: &str, category: &str, lang: &str, median_ms: f64) {
+    // A benchmark repeated in a later comparison table keeps its first value
+    if samples.iter().any(|s| s.benchmark == benchmark && s.language == lang) {
+        return;
+    }
+    samples.push(Sample {
+        benchmark: benchmark.to_string(),
+        category: category.to_string(),
+        language: lang.to_string(),
+        median_ms,
+        iterations: 0,
+    });
+}
+
+fn find_date(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+    (0..bytes.len().saturating_sub(9))
+        .filter(|&i| text.is_char_boundary(i) && text.is_char_boundary(i + 10))
+        .map(|i| &text[i..i + 10])
+        .find(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok())
+        .map(str::to_string)
+}
+
+/// First `v0.33.x`-style token.
+fn find_version(text: &str) -> Option<String> {
+    text.char_indices()
+        .filter(|(i, c)| *c == 'v' && text[i + 1..].starts_with(|d: char| d.is_ascii_digit()))
+        .map(|(i, _)| {
+            let version: String = text[i..]
+                .chars()
+                .enumerate()
+                .take_while(|(n, c)| *n == 0 || c.is_ascii_digit() || *c == '.' || *c == 'x')
+                .map(|(_, c)| c)
+                .collect();
+            version.trim_end_matches('.').to_string()
+        })
+        .find(|v| v.contains('.'))
+}
+
+/// `2026-01-08_phase33.5_comprehensive.md` -> `phase33.5`
+fn find_phase(file_name: &str) -> Option<String> {
+    let lower = file_name.to_lowercase();
+    let start = lower.find("phase")?;
+    let phase: String = lower[start..]
+        .chars()
+        .enumerate()
+        .take_while(|(n, c)| *n < 5 || c.is_ascii_digit() || *c == '.')
+        .map(|(_, c)| c)
+        .collect();
+    let phase = phase.trim_end_matches('.');
+    (phase.len() > 5).then(|| phase.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn known() -> BTreeMap<String, String> {
+        ["fibonacci", "reverse-complement", "json_parse"]
+            .iter()
+            .map(|name| (name.to_string(), "compute".to_string()))
+            .collect()
+    }
+
+    fn medians(samples: &[Sample]) -> Vec<(&str, &str, f64)> {
+        samples
+            .iter()
+            .map(|s| (s.benchmark.as_str(), s.language.as_str(), s.median_ms))
+            .collect()
+    }
+
+    #[test]
+    fn benchmark_tables_read_language_columns() {
+        let text = "\
+| Benchmark | C (ms) | BMB (ms) | Ratio |
+|-----------|--------|----------|-------|
+| **fibonacci(35)** | ~57ms | 54.5 | 0.95x |
+| `reverse-complement` | 12 | N/A | - |
+| nosuch | 1 | 2 | 2.0x |
+| Total | - | - | - |
+";
+        let mut unmatched = Vec::new();
+        let samples = markdown_samples(text, &known(), "c", &mut unmatched);
+        assert_eq!(
+            medians(&samples),
+            vec![
+                ("fibonacci", "c", 57.0),
+                ("fibonacci", "bmb", 54.5),
+                ("reverse-complement", "c", 12.0)
+            ]
+        );
+        assert_eq!(unmatched, vec!["nosuch"]);
+    }
+
+    #[test]
+    fn transposed_and_median_tables() {
+        let text = "\
+| Language | fibonacci(35) | Relative |
+|----------|---------------|----------|
+| Rust     | ~57ms         | 1.00x    |
+| BMB      | ~54ms         | 0.95x    |
+
+| Benchmark | Median (ms) |
+|-----------|-------------|
+| json_parse | .023 |
+";
+        let mut unmatched = Vec::new();
+        let samples = markdown_samples(text, &known(), "rust", &mut unmatched);
+        assert_eq!(
+            medians(&samples),
+            vec![("fibonacci", "rust", 57.0), ("fibonacci", "bmb", 54.0), ("json_parse", "rust", 0.023)]
+        );
+        assert!(unmatched.is_empty());
+    }
+
+    #[test]
+    fn language_time_tables_are_unsupported() {
+        let text = "\
+| Language | Time (ms) | Ratio |
+|----------|-----------|-------|
+| Rust (native) | ~56 | baseline |
+";
+        let mut unmatched = Vec::new();
+        assert!(markdown_samples(text, &known(), "c", &mut unmatched).is_empty());
+        assert!(unmatched.is_empty());
+        assert_eq!(unsupported_tables(text), 1);
+    }
+
+    #[test]
+    fn csv_columns_are_scaled_to_milliseconds() {
+        let text = "benchmark,c_time_s,bmb_time_ms,notes\nfibonacci,0.057,54,x\nk-nucleotide,0.1,90,\n";
+        let mut unmatched = Vec::new();
+        let samples = csv_samples(text, &known(), &mut unmatched);
+        assert_eq!(medians(&samples), vec![("fibonacci", "c", 57.0), ("fibonacci", "bmb", 54.0)]);
+        assert_eq!(unmatched, vec!["k-nucleotide"]);
+    }
+
+    #[test]
+    fn gate_verdicts_take_the_first_verdict_per_gate() {
+        let text = "\
+### Gate 1: Interpreter ✅ PASSED
+### Gate #3.1: Compute Parity ⚠️ PARTIAL
+Gate Status: Gate #3.1 PASSED
+Gate 4 is planned
+";
+        assert_eq!(
+            gate_verdicts(text),
+            vec![("Gate #1".to_string(), true), ("Gate #3.1".to_string(), false)]
+        );
+    }
+
+    #[test]
+    fn header_fields() {
+        assert_eq!(find_date("**Date**: 2026-01-08"), Some("2026-01-08".to_string()));
+        assert_eq!(find_date("2026-13-40"), None);
+        assert_eq!(find_version("BMB Version: v0.33.x (native)"), Some("v0.33.x".to_string()));
+        assert_eq!(find_version("baseline_v0.31.8.md"), Some("v0.31.8".to_string()));
+        assert_eq!(find_version("v2 only"), None);
+        assert_eq!(find_phase("2026-01-08_phase33.5_comprehensive.md"), Some("phase33.5".to_string()));
+        assert_eq!(find_phase("phase_notes.md"), None);
+        assert_eq!(parse_date("2026-1-8"), Ok("2026-01-08".to_string()));
+        assert_eq!(parse_date("2026-02-30"), Err("'2026-02-30' is not a YYYY-MM-DD date".to_string()));
+    }
+}
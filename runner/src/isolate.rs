@@ -0,0 +1,225 @@
+//! CPU pinning, priority and ASLR control for measured processes.
+//!
+//! The settings are applied in the forked child just before `exec`, so only
+//! the benchmark executables are affected, not the compilers or the runner.
+//! They are configured once per invocation and recorded with the results.
+
+use clap::Args;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+use std::sync::OnceLock;
+
+static CONFIG: OnceLock<Isolation> = OnceLock::new();
+
+/// Command-line options shared by `run`, `compare` and `gate`.
+#[derive(Args, Debug, Clone, Default)]
+pub struct IsolationArgs {
+    /// Pin benchmark processes to these CPUs (e.g. 3 or 2,3 or 4-7)
+    #[arg(long, value_name = "CPUS")]
+    pub pin: Option<String>,
+
+    /// Nice value for benchmark processes; negative raises priority and needs CAP_SYS_NICE
+    #[arg(long, allow_hyphen_values = true)]
+    pub nice: Option<i32>,
+
+    /// Disable address space layout randomization for benchmark processes
+    #[arg(long)]
+    pub no_aslr: bool,
+}
+
+/// Scheduling settings in effect for a run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Isolation {
+    /// CPUs the benchmark processes were pinned to; empty if unpinned
+    #[serde(default)]
+    pub cpus: Vec<usize>,
+    #[serde(default)]
+    pub nice: Option<i32>,
+    #[serde(default)]
+    pub aslr_disabled: bool,
+}
+
+impl Isolation {
+    fn is_empty(&self) -> bool {
+        self.cpus.is_empty() && self.nice.is_none() && !self.aslr_disabled
+    }
+
+    fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.cpus.is_empty() {
+            let cpus: Vec<String> = self.cpus.iter().map(|c| c.to_string()).collect();
+            parts.push(format!("pinned to CPU {}", cpus.join(",")));
+        }
+        if let Some(nice) = self.nice {
+            parts.push(format!("nice {}", nice));
+        }
+        if self.aslr_disabled {
+            parts.push("ASLR off".to_string());
+        }
+        parts.join(", ")
+    }
+}
+
+/// Validate the options and make them apply to every later [`command`].
+/// Exits with code 2 if they cannot be honoured.
+pub fn init(args: &IsolationArgs) {
+    let isolation = match from_args(args) {
+        Ok(isolation) => isolation,
+        Err(e) => {
+            println!("{} {}", "Error:".red(), e);
+            std::process::exit(2);
+        }
+    };
+    if isolation.is_empty() {
+        return;
+    }
+
+    let _ = CONFIG.set(isolation);
+
+    // Probe once so a missing permission fails loudly here instead of
+    // silently dropping every sample later
+    if let Err(e) = command(Path::new("true")).status() {
+        println!("{} cannot apply scheduling settings: {}", "Error:".red(), e);
+        std::process::exit(2);
+    }
+
+    if let Some(isolation) = current() {
+        println!("{} {}", "Isolation:".cyan(), isolation.summary());
+    }
+}
+
+/// Settings in effect, if any were requested.
+pub fn current() -> Option<&'static Isolation> {
+    CONFIG.get()
+}
+
+/// A command for a measured executable, with the configured settings
+/// applied to the child process.
+pub fn command(program: &Path) -> Command {
+    let mut cmd = Command::new(program);
+    if let Some(isolation) = CONFIG.get() {
+        apply(&mut cmd, isolation);
+    }
+    cmd
+}
+
+#[cfg(target_os = "linux")]
+fn from_args(args: &IsolationArgs) -> Result<Isolation, String> {
+    let cpus = match &args.pin {
+        Some(list) => parse_cpu_list(list)?,
+        None => Vec::new(),
+    };
+
+    let allowed = allowed_cpus();
+    if let Some(cpu) = cpus.iter().find(|c| !allowed.contains(c)) {
+        return Err(format!("CPU {} is not available (allowed: {:?})", cpu, allowed));
+    }
+    if let Some(nice) = args.nice {
+        if !(-20..=19).contains(&nice) {
+            return Err(format!("nice value {} is outside -20..=19", nice));
+        }
+    }
+
+    Ok(Isolation {
+        cpus,
+        nice: args.nice,
+        aslr_disabled: args.no_aslr,
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn from_args(args: &IsolationArgs) -> Result<Isolation, String> {
+    if args.pin.is_some() || args.nice.is_some() || args.no_aslr {
+        return Err("--pin, --nice and --no-aslr are only supported on Linux".to_string());
+    }
+    Ok(Isolation::default())
+}
+
+/// "0,2-3" -> [0, 2, 3]
+#[cfg(target_os = "linux")]
+fn parse_cpu_list(list: &str) -> Result<Vec<usize>, String> {
+    let mut cpus = Vec::new();
+    for part in list.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        // Checked before a range is expanded, so "0-999999999" is refused
+        // rather than allocated
+        let parse = |s: &str| match s.trim().parse::<usize>() {
+            Ok(cpu) if cpu < libc::CPU_SETSIZE as usize => Ok(cpu),
+            Ok(cpu) => Err(format!("CPU {} is beyond the highest CPU number {}", cpu, libc::CPU_SETSIZE - 1)),
+            Err(_) => Err(format!("invalid CPU list '{}'", list)),
+        };
+        match part.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = (parse(start)?, parse(end)?);
+                if start > end {
+                    return Err(format!("invalid CPU range '{}'", part));
+                }
+                cpus.extend(start..=end);
+            }
+            None => cpus.push(parse(part)?),
+        }
+    }
+    cpus.sort();
+    cpus.dedup();
+    if cpus.is_empty() {
+        return Err(format!("invalid CPU list '{}'", list));
+    }
+    Ok(cpus)
+}
+
+/// CPUs this process may run on.
+#[cfg(target_os = "linux")]
+fn allowed_cpus() -> Vec<usize> {
+    // SAFETY: cpu_set_t is plain data and sched_getaffinity only writes into it
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        if libc::sched_getaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &mut set) != 0 {
+            return Vec::new();
+        }
+        (0..libc::CPU_SETSIZE as usize).filter(|&cpu| libc::CPU_ISSET(cpu, &set)).collect()
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn apply(cmd: &mut Command, isolation: &Isolation) {
+    use std::os::unix::process::CommandExt;
+
+    // Everything the child needs is prepared here; between fork and exec
+    // only async-signal-safe system calls are made
+    let pin = !isolation.cpus.is_empty();
+    // SAFETY: zeroed cpu_set_t is an empty set; CPU_SET only sets bits
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    for &cpu in &isolation.cpus {
+        unsafe { libc::CPU_SET(cpu, &mut set) };
+    }
+    let nice = isolation.nice;
+    let no_aslr = isolation.aslr_disabled;
+
+    // SAFETY: the closure only calls sched_setaffinity, setpriority and
+    // personality, which do not allocate or take locks
+    unsafe {
+        cmd.pre_exec(move || {
+            if pin && libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            if let Some(nice) = nice {
+                if libc::setpriority(libc::PRIO_PROCESS, 0, nice) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+            if no_aslr {
+                let persona = libc::personality(0xffff_ffff);
+                if persona == -1
+                    || libc::personality((persona | libc::ADDR_NO_RANDOMIZE) as libc::c_ulong) == -1
+                {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+            Ok(())
+        });
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn apply(_cmd: &mut Command, _isolation: &Isolation) {}
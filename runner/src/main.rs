@@ -1,11 +1,31 @@
 use clap::{Parser, Subcommand};
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::time::{Duration, Instant};
+use std::time::Instant;
+
+mod ab;
+mod calibrate;
+mod catalog;
+mod diff;
+mod fuzz;
+mod gate;
+mod gate_report;
+mod history;
+mod host;
+mod import;
+mod isolate;
+mod manifest;
+mod preflight;
+mod report;
+mod results;
+mod rng;
+mod schedule;
+mod select;
+mod stats;
+mod validate;
 
 #[derive(Parser)]
 #[command(name = "benchmark-bmb")]
@@ -19,13 +39,8 @@ struct Cli {
 enum Commands {
     /// Run benchmarks
     Run {
-        /// Benchmark name or --all
-        #[arg(default_value = "all")]
-        name: String,
-
-        /// Category filter (compute, memory, realworld, contract)
-        #[arg(short, long)]
-        category: Option<String>,
+        #[command(flatten)]
+        select: select::SelectArgs,
 
         /// Number of iterations
         #[arg(short, long, default_value = "5")]
@@ -34,21 +49,64 @@ enum Commands {
         /// Warm-up iterations
         #[arg(short, long, default_value = "2")]
         warmup: u32,
+
+        /// Session label recorded in history (e.g. phase35)
+        #[arg(long)]
+        session: Option<String>,
+
+        /// Refuse to measure when the preflight noise checks warn
+        #[arg(long)]
+        strict: bool,
+
+        #[command(flatten)]
+        isolation: isolate::IsolationArgs,
+
+        #[command(flatten)]
+        schedule: schedule::ScheduleArgs,
     },
-    /// Compare languages for a benchmark
+    /// Compare languages for a benchmark or category
     Compare {
-        /// Benchmark name
-        name: String,
+        #[command(flatten)]
+        select: select::SelectArgs,
 
-        /// Languages to compare (comma-separated: c,bmb)
+        /// Implementations to compare (comma-separated: c,rust,bmb or variants like bmb:fp)
         #[arg(short, long, default_value = "c,bmb")]
         langs: String,
+
+        /// Number of iterations
+        #[arg(short, long, default_value = "10")]
+        iterations: u32,
+
+        /// Warm-up iterations
+        #[arg(short, long, default_value = "2")]
+        warmup: u32,
+
+        /// Confidence level for ratio intervals
+        #[arg(long, default_value = "0.95", value_parser = stats::parse_confidence)]
+        confidence: f64,
+
+        /// Baseline BMB compiler binary for an A/B comparison
+        #[arg(long, requires = "bmb_b")]
+        bmb_a: Option<PathBuf>,
+
+        /// Candidate BMB compiler binary for an A/B comparison
+        #[arg(long, requires = "bmb_a")]
+        bmb_b: Option<PathBuf>,
+
+        #[command(flatten)]
+        isolation: isolate::IsolationArgs,
+
+        #[command(flatten)]
+        schedule: schedule::ScheduleArgs,
     },
     /// List available benchmarks
     List {
-        /// Category filter
-        #[arg(short, long)]
-        category: Option<String>,
+        #[command(flatten)]
+        select: select::SelectArgs,
+
+        /// Output format (text, json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
     },
     /// Create new benchmark scaffold
     New {
@@ -61,22 +119,33 @@ enum Commands {
     },
     /// Validate benchmark implementations
     Validate {
-        /// Benchmark name
-        name: String,
+        #[command(flatten)]
+        select: select::SelectArgs,
     },
     /// Generate report
     Report {
-        /// Output format (text, json, html)
+        /// Output format (text, json, html, markdown)
         #[arg(short, long, default_value = "text")]
         format: String,
 
-        /// Output directory
-        #[arg(short, long, default_value = "results")]
-        output: String,
+        /// Also rewrite the marked benchmark tables in README.md
+        #[arg(long)]
+        update_readme: bool,
+
+        /// Output directory (defaults to results/)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Result file to render (defaults to the latest stored run)
+        #[arg(short, long)]
+        run: Option<PathBuf>,
+
+        #[command(flatten)]
+        select: select::SelectArgs,
     },
     /// Verify benchmark gate requirements
     Gate {
-        /// Gate number (1, 2, 3.1, 3.2, 3.3, 4)
+        /// Gate id as defined in gates.toml (e.g. 3.1)
         #[arg(default_value = "3.1")]
         gate: String,
 
@@ -84,9 +153,160 @@ enum Commands {
         #[arg(short, long, default_value = "5")]
         iterations: u32,
 
+        /// Confidence level for ratio intervals
+        #[arg(long, default_value = "0.95", value_parser = stats::parse_confidence)]
+        confidence: f64,
+
+        /// Seconds the gate may spend resampling inconclusive benchmarks
+        #[arg(long, default_value = "60")]
+        budget: u64,
+
+        /// Also write machine-readable results (text, json, junit)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+
+        /// File for --format json|junit (defaults to results/gate-<id>.json|xml)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Pinned run for a regression gate, overriding its `against`
+        /// (session label, git revision, BMB version or result file)
+        #[arg(long)]
+        against: Option<String>,
+
         /// Verbose output
         #[arg(short, long)]
         verbose: bool,
+
+        /// Session label recorded in history (e.g. phase35)
+        #[arg(long)]
+        session: Option<String>,
+
+        /// Refuse to measure when the preflight noise checks warn
+        #[arg(long)]
+        strict: bool,
+
+        #[command(flatten)]
+        isolation: isolate::IsolationArgs,
+
+        #[command(flatten)]
+        schedule: schedule::ScheduleArgs,
+    },
+    /// Import legacy markdown/CSV results into the run history
+    Import {
+        /// Files to import (defaults to every .md and .csv in results/)
+        files: Vec<PathBuf>,
+
+        /// Date to record, required for files without one (YYYY-MM-DD)
+        #[arg(long, value_parser = import::parse_date)]
+        date: Option<String>,
+
+        /// BMB version to record, overriding the file's
+        #[arg(long)]
+        bmb_version: Option<String>,
+
+        /// Session label to record, overriding the one derived from the file name
+        #[arg(long)]
+        session: Option<String>,
+
+        /// Show what would be imported without writing the history
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Show benchmark trends and gate milestones from the run history
+    History {
+        /// Benchmark to show the trend of (omit for an overview of runs)
+        benchmark: Option<String>,
+
+        /// Only show this language
+        #[arg(short, long)]
+        language: Option<String>,
+
+        /// Only show runs with this session label
+        #[arg(short, long)]
+        session: Option<String>,
+
+        /// Number of most recent runs to show
+        #[arg(short = 'n', long, default_value = "20")]
+        limit: usize,
+    },
+    /// Diff two saved result files and flag regressions
+    Diff {
+        /// Older result file (JSON)
+        old: PathBuf,
+
+        /// Newer result file (JSON)
+        new: PathBuf,
+
+        /// Minimum change to report, in percent (defaults to this machine's
+        /// calibrated noise floor, or 5 without a calibration)
+        #[arg(short, long)]
+        threshold: Option<f64>,
+
+        /// Confidence level for change intervals
+        #[arg(long, default_value = "0.95", value_parser = stats::parse_confidence)]
+        confidence: f64,
+
+        /// Only compare this language
+        #[arg(short, long)]
+        language: Option<String>,
+
+        /// Exit code when regressions are found (0 to never fail)
+        #[arg(long, default_value = "1")]
+        exit_code: i32,
+    },
+    /// Measure this machine's noise floor by timing a binary against itself
+    Calibrate {
+        #[command(flatten)]
+        select: select::SelectArgs,
+
+        /// Implementation to time against itself
+        #[arg(short, long, default_value = "c")]
+        lang: String,
+
+        /// Iterations per side in each trial
+        #[arg(short, long, default_value = "5")]
+        iterations: u32,
+
+        /// Warm-up iterations per trial
+        #[arg(short, long, default_value = "1")]
+        warmup: u32,
+
+        /// A/A trials per benchmark
+        #[arg(short, long, default_value = "10")]
+        trials: u32,
+
+        #[command(flatten)]
+        isolation: isolate::IsolationArgs,
+
+        #[command(flatten)]
+        schedule: schedule::ScheduleArgs,
+    },
+    /// Differential fuzzing of the bootstrap lexer and parser implementations
+    Fuzz {
+        /// Target benchmark (lex_bootstrap, parse_bootstrap, all)
+        #[arg(default_value = "all")]
+        target: String,
+
+        /// Number of generated inputs per target
+        #[arg(short = 'n', long, default_value = "100")]
+        cases: u32,
+
+        /// Input generator (random, grammar, mixed)
+        #[arg(short, long, default_value = "mixed")]
+        mode: String,
+
+        /// RNG seed (defaults to the current time)
+        #[arg(short, long)]
+        seed: Option<u64>,
+
+        /// Maximum generated input length in bytes
+        #[arg(long, default_value = "512")]
+        max_len: usize,
+
+        /// Harness runs spent minimizing each divergent input
+        #[arg(long, default_value = "100")]
+        shrink_steps: u32,
     },
 }
 
@@ -100,6 +320,38 @@ struct BenchmarkResult {
     min_ms: f64,
     max_ms: f64,
     iterations: u32,
+    #[serde(default)]
+    mean_ms: f64,
+    #[serde(default)]
+    stddev_ms: f64,
+    #[serde(default)]
+    warmup: u32,
+}
+
+impl BenchmarkResult {
+    fn from_samples(name: &str, category: &str, language: &str, times_ms: Vec<f64>, warmup: u32) -> Self {
+        let n = times_ms.len() as f64;
+        let mean = times_ms.iter().sum::<f64>() / n;
+        let variance = if times_ms.len() > 1 {
+            times_ms.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / (n - 1.0)
+        } else {
+            0.0
+        };
+
+        BenchmarkResult {
+            name: name.to_string(),
+            category: category.to_string(),
+            language: language.to_string(),
+            median_ms: stats::median(&times_ms),
+            min_ms: times_ms.iter().cloned().fold(f64::INFINITY, f64::min),
+            max_ms: times_ms.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+            iterations: times_ms.len() as u32,
+            mean_ms: mean,
+            stddev_ms: variance.sqrt(),
+            warmup,
+            times_ms,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -110,79 +362,226 @@ struct ComparisonReport {
     timestamp: String,
 }
 
+impl ComparisonReport {
+    /// Report for one benchmark directory (`benches/<category>/<name>`).
+    /// The baseline is C when present, otherwise the first language.
+    fn from_measurements(path: &Path, measurements: &[(String, Vec<f64>)], warmup: u32) -> Self {
+        let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let category = manifest::category(path);
+        let baseline = measurements
+            .iter()
+            .find(|(lang, _)| lang == "c")
+            .or_else(|| measurements.first())
+            .map(|(lang, _)| lang.clone())
+            .unwrap_or_default();
+
+        ComparisonReport {
+            results: measurements
+                .iter()
+                .map(|(lang, times)| BenchmarkResult::from_samples(&name, &category, lang, times.clone(), warmup))
+                .collect(),
+            benchmark: name,
+            baseline,
+            timestamp: chrono::Local::now().to_rfc3339(),
+        }
+    }
+}
+
 fn main() {
     let cli = Cli::parse();
 
     match cli.command {
         Commands::Run {
-            name,
-            category,
+            select,
             iterations,
             warmup,
-        } => run_benchmarks(&name, category.as_deref(), iterations, warmup),
-        Commands::Compare { name, langs } => compare_benchmark(&name, &langs),
-        Commands::List { category } => list_benchmarks(category.as_deref()),
+            session,
+            strict,
+            isolation,
+            schedule,
+        } => {
+            isolate::init(&isolation);
+            schedule::init(&schedule);
+            run_benchmarks(&select, iterations, warmup, session, strict)
+        }
+        Commands::Compare {
+            select,
+            langs,
+            iterations,
+            warmup,
+            confidence,
+            bmb_a,
+            bmb_b,
+            isolation,
+            schedule,
+        } => {
+            isolate::init(&isolation);
+            schedule::init(&schedule);
+            match (bmb_a, bmb_b) {
+                (Some(a), Some(b)) => {
+                    compare_bmb_compilers(&select, &a, &b, iterations, warmup, confidence)
+                }
+                _ => compare_benchmarks(&select, &langs, iterations, warmup, confidence),
+            }
+        }
+        Commands::List { select, format } => list_benchmarks(&select, &format),
         Commands::New { name, category } => create_benchmark(&name, &category),
-        Commands::Validate { name } => validate_benchmark(&name),
-        Commands::Report { format, output } => generate_report(&format, &output),
-        Commands::Gate { gate, iterations, verbose } => verify_gate(&gate, iterations, verbose),
+        Commands::Validate { select } => validate_benchmarks(&select),
+        Commands::Report {
+            format,
+            update_readme,
+            output,
+            run,
+            select,
+        } => generate_report(&format, update_readme, output.as_deref(), run.as_deref(), &select),
+        Commands::Gate {
+            gate,
+            iterations,
+            confidence,
+            budget,
+            format,
+            output,
+            against,
+            verbose,
+            session,
+            strict,
+            isolation,
+            schedule,
+        } => {
+            isolate::init(&isolation);
+            schedule::init(&schedule);
+            let sampling = gate::Sampling {
+                iterations,
+                confidence,
+                budget: std::time::Duration::from_secs(budget),
+            };
+            let output = GateOutput {
+                format,
+                path: output,
+            };
+            verify_gate(&gate, &sampling, &output, against.as_deref(), verbose, session, strict)
+        }
+        Commands::Import {
+            files,
+            date,
+            bmb_version,
+            session,
+            dry_run,
+        } => import_results(
+            &files,
+            import::Overrides {
+                date,
+                bmb_version,
+                session,
+            },
+            dry_run,
+        ),
+        Commands::History {
+            benchmark,
+            language,
+            session,
+            limit,
+        } => show_history(benchmark.as_deref(), language.as_deref(), session.as_deref(), limit),
+        Commands::Diff {
+            old,
+            new,
+            threshold,
+            confidence,
+            language,
+            exit_code,
+        } => diff_results(&old, &new, threshold, confidence, language.as_deref(), exit_code),
+        Commands::Calibrate {
+            select,
+            lang,
+            iterations,
+            warmup,
+            trials,
+            isolation,
+            schedule,
+        } => {
+            isolate::init(&isolation);
+            schedule::init(&schedule);
+            calibrate_noise(&select, &lang, iterations, warmup, trials)
+        }
+        Commands::Fuzz {
+            target,
+            cases,
+            mode,
+            seed,
+            max_len,
+            shrink_steps,
+        } => fuzz_bootstrap(&target, cases, &mode, seed, max_len, shrink_steps),
+    }
+}
+
+/// Benchmarks picked by the selector options; prints why and returns
+/// `None` when there are none.
+fn selected_benchmarks(benches_dir: &Path, select: &select::SelectArgs) -> Option<Vec<(String, PathBuf)>> {
+    match select::select(benches_dir, select) {
+        Ok(benchmarks) if benchmarks.is_empty() => {
+            println!("{} No benchmarks match '{}'.", "Error:".red(), select.describe());
+            None
+        }
+        Ok(benchmarks) => Some(benchmarks),
+        Err(e) => {
+            println!("{} {}", "Error:".red(), e);
+            None
+        }
     }
 }
 
-fn run_benchmarks(name: &str, category: Option<&str>, iterations: u32, warmup: u32) {
+fn run_benchmarks(
+    select: &select::SelectArgs,
+    iterations: u32,
+    warmup: u32,
+    session: Option<String>,
+    strict: bool,
+) {
     println!("{}", "=== BMB Benchmark Suite ===".cyan().bold());
     println!();
 
     let benches_dir = find_benches_dir();
-    let benchmarks = discover_benchmarks(&benches_dir, category);
-
-    if benchmarks.is_empty() {
-        println!("{}", "No benchmarks found.".yellow());
+    let Some(benchmarks) = selected_benchmarks(&benches_dir, select) else {
         return;
-    }
-
-    let benchmarks: Vec<_> = if name == "all" {
-        benchmarks
-    } else {
-        benchmarks
-            .into_iter()
-            .filter(|(n, _)| n == name)
-            .collect()
     };
 
-    if benchmarks.is_empty() {
-        println!("{} Benchmark '{}' not found.", "Error:".red(), name);
-        return;
-    }
+    let preflight_warnings = preflight::check(strict);
 
+    let mut reports = Vec::new();
     for (bench_name, bench_path) in &benchmarks {
         println!("{} {}", "Running:".green(), bench_name);
-        run_single_benchmark(bench_name, bench_path, iterations, warmup);
+        reports.extend(run_single_benchmark(bench_name, bench_path, iterations, warmup));
         println!();
     }
-}
-
-fn run_single_benchmark(name: &str, path: &Path, iterations: u32, warmup: u32) {
-    let mut results: HashMap<String, Vec<f64>> = HashMap::new();
 
-    // Find language implementations
-    for entry in fs::read_dir(path).unwrap().flatten() {
-        let lang_dir = entry.path();
-        if !lang_dir.is_dir() {
-            continue;
-        }
-
-        let lang = lang_dir.file_name().unwrap().to_string_lossy().to_string();
+    if !reports.is_empty() {
+        let mut doc = results::RunDocument::new("run", reports);
+        doc.session = session;
+        doc.preflight_warnings = preflight_warnings;
+        save_run(&benches_dir, doc);
+    }
+}
 
-        if let Some(times) = run_language_benchmark(&lang_dir, &lang, iterations, warmup) {
-            results.insert(lang, times);
-        }
+/// Save a run document under results/ and append it to the history.
+fn save_run(benches_dir: &Path, doc: results::RunDocument) {
+    let results_dir = results::results_dir(benches_dir);
+    let saved = doc.save(&results_dir).and_then(|path| {
+        history::append(&results_dir, &[history::HistoryRecord::from_run(&doc, &path)])?;
+        Ok(path)
+    });
+    match saved {
+        Ok(path) => println!("{} {}", "Saved:".green(), path.display()),
+        Err(e) => eprintln!("{} could not save results: {}", "Warning:".yellow(), e),
     }
+}
+
+fn run_single_benchmark(_name: &str, path: &Path, iterations: u32, warmup: u32) -> Option<ComparisonReport> {
+    let results = measure_benchmark(path, None, iterations, warmup);
 
     // Print results
     if results.is_empty() {
         println!("  {}", "No runnable implementations found".yellow());
-        return;
+        return None;
     }
 
     println!();
@@ -194,13 +593,14 @@ fn run_single_benchmark(name: &str, path: &Path, iterations: u32, warmup: u32) {
 
     // Find baseline (C or first language)
     let baseline = results
-        .get("c")
-        .or_else(|| results.values().next())
-        .map(|times| median(times))
+        .iter()
+        .find(|(lang, _)| lang == "c")
+        .or_else(|| results.first())
+        .map(|(_, times)| stats::median(times))
         .unwrap_or(1.0);
 
     for (lang, times) in &results {
-        let med = median(times);
+        let med = stats::median(times);
         let min = times.iter().cloned().fold(f64::INFINITY, f64::min);
         let max = times.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
         let relative = med / baseline;
@@ -220,56 +620,162 @@ fn run_single_benchmark(name: &str, path: &Path, iterations: u32, warmup: u32) {
             status
         );
     }
+
+    Some(ComparisonReport::from_measurements(path, &results, warmup))
 }
 
-fn run_language_benchmark(
-    dir: &Path,
-    lang: &str,
+/// Build and time the implementations of one benchmark.
+///
+/// With `langs` given, only those implementations (`lang` or
+/// `lang:variant`) are measured, in that order; otherwise every
+/// implementation is measured in name order.
+fn measure_benchmark(
+    path: &Path,
+    langs: Option<&[String]>,
     iterations: u32,
     warmup: u32,
-) -> Option<Vec<f64>> {
-    let executable = match lang {
-        "c" => compile_c(dir)?,
-        "rust" => compile_rust(dir)?,
-        "bmb" => compile_bmb(dir)?,
-        _ => return None,
+) -> Vec<(String, Vec<f64>)> {
+    let specs: Vec<String> = match langs {
+        Some(langs) => langs.to_vec(),
+        None => implementations(path),
     };
 
-    let mut times = Vec::new();
+    // Build everything first so samples can be interleaved across implementations
+    let built: Vec<(String, PathBuf)> = specs
+        .into_iter()
+        .filter_map(|spec| match build_implementation(path, &spec) {
+            Some(exe) => Some((spec, exe)),
+            None => {
+                println!("  {} {} could not be built; not timed", "Skipped:".yellow(), spec.to_uppercase());
+                None
+            }
+        })
+        .collect();
+    let executables: Vec<&Path> = built.iter().map(|(_, exe)| exe.as_path()).collect();
+    let times = schedule::measure(&executables, iterations, warmup);
+
+    built
+        .into_iter()
+        .zip(times)
+        .filter(|((lang, _), times)| {
+            if times.is_empty() {
+                println!("  {} {} did not run successfully; not timed", "Skipped:".yellow(), lang.to_uppercase());
+            }
+            !times.is_empty()
+        })
+        .map(|((lang, _), times)| (lang, times))
+        .collect()
+}
+
+fn build_language(dir: &Path, lang: &str) -> Option<PathBuf> {
+    build_source(dir, lang, "main")
+}
+
+/// Build `<stem>.<ext>` in a language directory.
+fn build_source(dir: &Path, lang: &str, stem: &str) -> Option<PathBuf> {
+    match lang {
+        "c" => compile_c(dir, stem),
+        "rust" => compile_rust(dir, stem),
+        "bmb" => compile_bmb(dir, stem),
+        _ => None,
+    }
+}
 
-    // Warmup
-    for _ in 0..warmup {
-        let _ = Command::new(&executable).output();
+/// Split an implementation spec, `lang` or `lang:variant`, into its
+/// language and source stem (`main` or `main_<variant>`).
+fn implementation_source(spec: &str) -> (&str, String) {
+    match spec.split_once(':') {
+        Some((lang, variant)) => (lang, format!("main_{}", variant)),
+        None => (spec, "main".to_string()),
     }
+}
 
-    // Measure
-    for _ in 0..iterations {
-        let start = Instant::now();
-        let output = Command::new(&executable).output();
-        let elapsed = start.elapsed();
+/// Source file extension of a language directory.
+fn source_extension(lang: &str) -> Option<&'static str> {
+    match lang {
+        "c" => Some("c"),
+        "rust" => Some("rs"),
+        "bmb" => Some("bmb"),
+        _ => None,
+    }
+}
 
-        if output.is_ok() {
-            times.push(elapsed.as_secs_f64() * 1000.0);
+/// Implementation specs of a benchmark in name order: `lang` for each
+/// language's `main` source, plus `lang:variant` for the variants its
+/// manifest declares, or for every `main_<variant>` source if it declares
+/// none for that language.
+fn implementations(bench_path: &Path) -> Vec<String> {
+    let declared = manifest::Manifest::load(bench_path).map(|m| m.variants).unwrap_or_default();
+    let mut langs: Vec<String> = fs::read_dir(bench_path)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    langs.sort();
+
+    let mut specs = Vec::new();
+    for lang in langs {
+        let Some(ext) = source_extension(&lang) else {
+            continue;
+        };
+        let dir = bench_path.join(&lang);
+        if dir.join(format!("main.{}", ext)).exists() {
+            specs.push(lang.clone());
         }
-    }
 
-    if times.is_empty() {
-        None
-    } else {
-        Some(times)
+        let variants = match declared.get(&lang) {
+            Some(variants) => variants.clone(),
+            None => {
+                let suffix = format!(".{}", ext);
+                let mut found: Vec<String> = fs::read_dir(&dir)
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .filter_map(|e| {
+                        let file = e.file_name().to_string_lossy().to_string();
+                        file.strip_prefix("main_")?.strip_suffix(&suffix).map(str::to_string)
+                    })
+                    .collect();
+                found.sort();
+                found
+            }
+        };
+        specs.extend(variants.into_iter().map(|variant| format!("{}:{}", lang, variant)));
     }
+    specs
+}
+
+/// Build one implementation (`lang` or `lang:variant`) of a benchmark.
+fn build_implementation(bench_path: &Path, spec: &str) -> Option<PathBuf> {
+    let (lang, stem) = implementation_source(spec);
+    build_source(&bench_path.join(lang), lang, &stem)
+}
+
+/// Run an executable once and return its wall-clock time in milliseconds.
+/// A run that cannot start or is killed by a signal has no time.
+fn time_run(executable: &Path) -> Result<f64, String> {
+    let start = Instant::now();
+    let output = isolate::command(executable)
+        .output()
+        .map_err(|e| format!("failed to run: {}", e))?;
+    let elapsed = start.elapsed();
+
+    validate::completed(output.status)?;
+    Ok(elapsed.as_secs_f64() * 1000.0)
 }
 
-fn compile_c(dir: &Path) -> Option<PathBuf> {
-    let source = dir.join("main.c");
+fn compile_c(dir: &Path, stem: &str) -> Option<PathBuf> {
+    let source = dir.join(format!("{}.c", stem));
     if !source.exists() {
         return None;
     }
 
     let output = if cfg!(windows) {
-        dir.join("main.exe")
+        dir.join(format!("{}.exe", stem))
     } else {
-        dir.join("main")
+        dir.join(stem)
     };
 
     let status = Command::new("gcc")
@@ -286,16 +792,16 @@ fn compile_c(dir: &Path) -> Option<PathBuf> {
     }
 }
 
-fn compile_rust(dir: &Path) -> Option<PathBuf> {
-    let source = dir.join("main.rs");
+fn compile_rust(dir: &Path, stem: &str) -> Option<PathBuf> {
+    let source = dir.join(format!("{}.rs", stem));
     if !source.exists() {
         return None;
     }
 
     let output = if cfg!(windows) {
-        dir.join("main.exe")
+        dir.join(format!("{}.exe", stem))
     } else {
-        dir.join("main")
+        dir.join(stem)
     };
 
     let status = Command::new("rustc")
@@ -312,8 +818,8 @@ fn compile_rust(dir: &Path) -> Option<PathBuf> {
     }
 }
 
-fn compile_bmb(dir: &Path) -> Option<PathBuf> {
-    let source = dir.join("main.bmb");
+fn compile_bmb(dir: &Path, stem: &str) -> Option<PathBuf> {
+    let source = dir.join(format!("{}.bmb", stem));
     if !source.exists() {
         return None;
     }
@@ -321,22 +827,16 @@ fn compile_bmb(dir: &Path) -> Option<PathBuf> {
     // Get absolute path for wrapper scripts
     let abs_source = fs::canonicalize(&source).ok()?;
 
-    let output = dir.join("main");
-
     // Try native compilation first, fall back to interpreter
-    let status = Command::new("bmb")
-        .args(["build", "-o"])
-        .arg(&output)
-        .arg(&source)
-        .status();
-
-    match status {
-        Ok(s) if s.success() => Some(output),
-        _ => {
+    // Interpreter wrappers keep their historical name for main.bmb
+    let wrapper_stem = if stem == "main" { "run".to_string() } else { format!("run_{}", stem) };
+    match compile_bmb_native(dir, Path::new("bmb"), stem, stem) {
+        Some(output) => Some(output),
+        None => {
             // Create wrapper script for interpreter (platform-specific)
             #[cfg(windows)]
             {
-                let wrapper = dir.join("run.cmd");
+                let wrapper = dir.join(format!("{}.cmd", wrapper_stem));
                 // Use absolute path for reliability
                 let script = format!("@echo off\nbmb run \"{}\"\n", abs_source.display());
                 fs::write(&wrapper, script).ok()?;
@@ -344,7 +844,7 @@ fn compile_bmb(dir: &Path) -> Option<PathBuf> {
             }
             #[cfg(not(windows))]
             {
-                let wrapper = dir.join("run.sh");
+                let wrapper = dir.join(format!("{}.sh", wrapper_stem));
                 let script = format!("#!/bin/sh\nbmb run \"{}\"\n", abs_source.display());
                 fs::write(&wrapper, script).ok()?;
                 use std::os::unix::fs::PermissionsExt;
@@ -357,54 +857,182 @@ fn compile_bmb(dir: &Path) -> Option<PathBuf> {
     }
 }
 
-fn compare_benchmark(name: &str, langs: &str) {
-    println!(
-        "{} {} ({})",
-        "Comparing:".cyan().bold(),
-        name,
-        langs
-    );
-    // Implementation would compare specific languages
+/// Build `<stem>.bmb` (`main` or a `main_<variant>`) natively with a specific
+/// compiler binary, writing the executable to `output_name` in `dir`.
+fn compile_bmb_native(dir: &Path, compiler: &Path, stem: &str, output_name: &str) -> Option<PathBuf> {
+    let source = dir.join(format!("{}.bmb", stem));
+    if !source.exists() {
+        return None;
+    }
+
+    let output = dir.join(output_name);
+    let status = Command::new(compiler)
+        .args(["build", "-o"])
+        .arg(&output)
+        .arg(&source)
+        .status()
+        .ok()?;
+
+    if status.success() {
+        Some(output)
+    } else {
+        None
+    }
 }
 
-fn list_benchmarks(category: Option<&str>) {
-    println!("{}", "Available Benchmarks".cyan().bold());
-    println!();
+fn compare_benchmarks(
+    select: &select::SelectArgs,
+    langs: &str,
+    iterations: u32,
+    warmup: u32,
+    confidence: f64,
+) {
+    let langs: Vec<String> = langs
+        .split(',')
+        .map(|l| l.trim().to_lowercase())
+        .filter(|l| !l.is_empty())
+        .collect();
+
+    if langs.len() < 2 {
+        println!("{} Need at least two languages to compare", "Error:".red());
+        return;
+    }
+
+    let benches_dir = find_benches_dir();
+    let Some(benchmarks) = selected_benchmarks(&benches_dir, select) else {
+        return;
+    };
+
+    for (bench_name, bench_path) in &benchmarks {
+        println!("{} {} ({})", "Comparing:".cyan().bold(), bench_name, langs.join(", "));
+        let results = measure_benchmark(bench_path, Some(&langs), iterations, warmup);
+        print_comparison(&results, confidence);
+        println!();
+    }
+}
 
+fn compare_bmb_compilers(
+    select: &select::SelectArgs,
+    bmb_a: &Path,
+    bmb_b: &Path,
+    iterations: u32,
+    warmup: u32,
+    confidence: f64,
+) {
     let benches_dir = find_benches_dir();
-    let benchmarks = discover_benchmarks(&benches_dir, category);
+    let Some(benchmarks) = selected_benchmarks(&benches_dir, select) else {
+        return;
+    };
+    let benchmarks: Vec<_> = benchmarks.into_iter().filter(|(_, path)| path.join("bmb").is_dir()).collect();
 
     if benchmarks.is_empty() {
-        println!("{}", "No benchmarks found.".yellow());
+        println!("{} No selected benchmark has a BMB implementation.", "Error:".red());
         return;
     }
 
-    let mut current_category = String::new();
-    for (name, path) in benchmarks {
-        let cat = path
-            .parent()
-            .and_then(|p| p.file_name())
-            .map(|s| s.to_string_lossy().to_string())
-            .unwrap_or_default();
+    ab::compare_compilers(&benchmarks, bmb_a, bmb_b, iterations, warmup, confidence);
+}
+
+/// Print the pairwise ratio matrix and the winner of each pair.
+fn print_comparison(results: &[(String, Vec<f64>)], confidence: f64) {
+    if results.len() < 2 {
+        println!("  {}", "Fewer than two runnable implementations".yellow());
+        return;
+    }
+
+    println!();
+    println!("  {:<10} {:>12} {:>8}", "Language", "Median (ms)", "Samples");
+    println!("  {}", "-".repeat(32));
+    for (lang, times) in results {
+        println!("  {:<10} {:>12.2} {:>8}", lang.to_uppercase(), stats::median(times), times.len());
+    }
 
-        if cat != current_category {
-            println!("\n{}:", cat.to_uppercase().green());
-            current_category = cat;
+    // Matrix cell = row median / column median
+    println!();
+    println!("  Ratio matrix (row / column, {:.0}% CI):", confidence * 100.0);
+    print!("  {:<10}", "");
+    for (lang, _) in results {
+        print!(" {:>22}", lang.to_uppercase());
+    }
+    println!();
+    for (row_lang, row_times) in results {
+        print!("  {:<10}", row_lang.to_uppercase());
+        for (col_lang, col_times) in results {
+            if row_lang == col_lang {
+                print!(" {:>22}", "-");
+                continue;
+            }
+            let ci = stats::ratio_ci(row_times, col_times, confidence);
+            let cell = format!("{:.2} [{:.2}, {:.2}]", ci.estimate, ci.low, ci.high);
+            print!(" {:>22}", cell);
         }
+        println!();
+    }
 
-        // Find available languages
-        let langs: Vec<_> = fs::read_dir(&path)
-            .ok()
-            .map(|entries| {
-                entries
-                    .flatten()
-                    .filter(|e| e.path().is_dir())
-                    .map(|e| e.file_name().to_string_lossy().to_string())
-                    .collect()
-            })
-            .unwrap_or_default();
+    println!();
+    println!("  Winners:");
+    for (i, (a_lang, a_times)) in results.iter().enumerate() {
+        for (b_lang, b_times) in &results[i + 1..] {
+            let ci = stats::ratio_ci(a_times, b_times, confidence);
+            let pair = format!("{} vs {}", a_lang.to_uppercase(), b_lang.to_uppercase());
+            if !ci.excludes(1.0) {
+                println!(
+                    "  {:<16} {} (CI [{:.2}, {:.2}] includes 1.00)",
+                    pair,
+                    "no significant difference".yellow(),
+                    ci.low,
+                    ci.high
+                );
+            } else if ci.estimate < 1.0 {
+                println!(
+                    "  {:<16} {} {:.2}x faster",
+                    pair,
+                    a_lang.to_uppercase().green(),
+                    1.0 / ci.estimate
+                );
+            } else {
+                println!(
+                    "  {:<16} {} {:.2}x faster",
+                    pair,
+                    b_lang.to_uppercase().green(),
+                    ci.estimate
+                );
+            }
+        }
+    }
+}
 
-        println!("  {} [{}]", name, langs.join(", "));
+fn list_benchmarks(select: &select::SelectArgs, format: &str) {
+    if !catalog::FORMATS.contains(&format) {
+        println!(
+            "{} Unknown format: {}. Valid: {}",
+            "Error:".red(),
+            format,
+            catalog::FORMATS.join(", ")
+        );
+        return;
+    }
+
+    let benches_dir = find_benches_dir();
+    let Some(mut benchmarks) = selected_benchmarks(&benches_dir, select) else {
+        return;
+    };
+    benchmarks.sort_by_key(|(name, path)| (manifest::category(path), name.clone()));
+
+    // Latest results are a nicety; a broken history still lists everything
+    let records = history::load(&results::results_dir(&benches_dir)).unwrap_or_else(|e| {
+        eprintln!("{} {}", "Warning:".yellow(), e);
+        Vec::new()
+    });
+    let toolchains = catalog::toolchains();
+    let entries: Vec<catalog::Entry> = benchmarks
+        .iter()
+        .map(|(name, path)| catalog::Entry::new(name, path, &toolchains, &records))
+        .collect();
+
+    match format {
+        "json" => println!("{}", catalog::render_json(&toolchains, &entries)),
+        _ => catalog::print_text(&toolchains, &entries),
     }
 }
 
@@ -453,171 +1081,622 @@ fn main() -> i32 = 42;
     println!("{} Created at {}", "Success:".green(), bench_path.display());
 }
 
-fn validate_benchmark(name: &str) {
-    println!("Validating benchmark: {}", name);
-    // Implementation would run all implementations and compare outputs
-}
+fn validate_benchmarks(select: &select::SelectArgs) {
+    let benches_dir = find_benches_dir();
+    let Some(benchmarks) = selected_benchmarks(&benches_dir, select) else {
+        return;
+    };
 
-fn generate_report(format: &str, output: &str) {
-    println!("Generating {} report to {}", format, output);
-    // Implementation would generate formatted report
-}
+    let failed: Vec<&str> = benchmarks
+        .iter()
+        .filter(|(name, path)| !validate_benchmark(name, path))
+        .map(|(name, _)| name.as_str())
+        .collect();
 
-/// Gate criteria definitions
-struct GateCriteria {
-    name: &'static str,
-    description: &'static str,
-    benchmarks: &'static [&'static str],
-    threshold: f64,  // BMB/C ratio threshold (e.g., 1.10 = within 10%)
-    contract_threshold: f64,  // For contract benchmarks (e.g., 0.90 = 10% faster)
+    if benchmarks.len() > 1 {
+        println!("{}", "=".repeat(70));
+        if failed.is_empty() {
+            println!("{} {} benchmarks validated", "✓".green().bold(), benchmarks.len());
+        } else {
+            println!("{} {} of {} benchmarks failed: {}", "✗".red().bold(), failed.len(), benchmarks.len(), failed.join(", "));
+        }
+    }
+    if !failed.is_empty() {
+        std::process::exit(1);
+    }
 }
 
-const GATE_3_1: GateCriteria = GateCriteria {
-    name: "Gate #3.1",
-    description: "Compute within 10% of C, Contract >= 10% faster",
-    benchmarks: &["fibonacci", "mandelbrot", "spectral_norm", "n_body", "fannkuch", "binary_trees"],
-    threshold: 1.10,
-    contract_threshold: 0.90,
-};
+/// Checks that every implementation of one benchmark prints the reference
+/// answer; returns whether they all agree.
+fn validate_benchmark(name: &str, bench_path: &Path) -> bool {
+    println!("{} {}", "Validating:".cyan().bold(), name);
+    println!();
+
+    // Capture every buildable implementation
+    let mut answers: Vec<(String, Result<validate::Answer, String>)> = Vec::new();
+    for spec in implementations(bench_path) {
+        let answer = match build_implementation(bench_path, &spec) {
+            Some(exe) => validate::capture(&exe),
+            None => Err("build failed".to_string()),
+        };
+        answers.push((spec, answer));
+    }
 
-const GATE_3_2: GateCriteria = GateCriteria {
-    name: "Gate #3.2",
-    description: "All Benchmarks Game within 5% of C",
-    benchmarks: &["fibonacci", "mandelbrot", "spectral_norm", "n_body", "fannkuch", "binary_trees", "fasta", "reverse_complement"],
-    threshold: 1.05,
-    contract_threshold: 0.85,
-};
+    // Pinned output wins, then C, then the first implementation that ran
+    let (reference_name, reference) = match validate::expected(bench_path) {
+        Some(expected) => (validate::EXPECTED_OUTPUT_FILE.to_string(), expected),
+        None => match answers
+            .iter()
+            .find(|(lang, a)| lang == "c" && a.is_ok())
+            .or_else(|| answers.iter().find(|(_, a)| a.is_ok()))
+        {
+            Some((lang, Ok(answer))) => (lang.clone(), answer.clone()),
+            _ => {
+                println!("{} No implementation produced output.", "Error:".red());
+                println!();
+                return false;
+            }
+        },
+    };
 
-const GATE_3_3: GateCriteria = GateCriteria {
-    name: "Gate #3.3",
-    description: "3+ benchmarks faster than C, all contract faster",
-    benchmarks: &["fibonacci", "mandelbrot", "spectral_norm", "n_body", "fannkuch", "binary_trees"],
-    threshold: 1.00,  // At least 3 must be <= 1.0
-    contract_threshold: 0.95,
-};
+    println!("Reference: {}", reference_name);
+    println!("{}", "-".repeat(70));
 
-fn verify_gate(gate: &str, iterations: u32, verbose: bool) {
-    println!("{}", "=== BMB Benchmark Gate Verification ===".cyan().bold());
+    let mut mismatches = 0;
+    for (lang, answer) in &answers {
+        let verdict = answer
+            .as_ref()
+            .map_err(String::clone)
+            .and_then(|a| validate::compare(&reference, a));
+        match verdict {
+            Ok(()) => println!("  {:<10} {}", lang.to_uppercase(), "OK".green()),
+            Err(reason) => {
+                mismatches += 1;
+                println!("  {:<10} {} {}", lang.to_uppercase(), "FAIL".red(), reason);
+            }
+        }
+    }
+
+    println!();
+    if mismatches > 0 {
+        println!("{} {} implementation(s) disagree", "✗".red().bold(), mismatches);
+    } else {
+        println!("{} All implementations agree", "✓".green().bold());
+    }
     println!();
+    mismatches == 0
+}
 
-    let criteria = match gate {
-        "3.1" => &GATE_3_1,
-        "3.2" => &GATE_3_2,
-        "3.3" => &GATE_3_3,
-        _ => {
-            println!("{} Unknown gate: {}. Valid: 3.1, 3.2, 3.3", "Error:".red(), gate);
+fn generate_report(
+    format: &str,
+    update_readme: bool,
+    output: Option<&Path>,
+    run: Option<&Path>,
+    select: &select::SelectArgs,
+) {
+    if !["text", "json", "html", "markdown"].contains(&format) {
+        println!(
+            "{} Unknown format: {}. Valid: text, json, html, markdown",
+            "Error:".red(),
+            format
+        );
+        return;
+    }
+
+    let benches_dir = find_benches_dir();
+    let results_dir = results::results_dir(&benches_dir);
+    let Some(source) = run.map(Path::to_path_buf).or_else(|| results::latest_run(&results_dir)) else {
+        println!("{} No stored runs found in {}", "Error:".red(), results_dir.display());
+        return;
+    };
+
+    let mut doc = match results::load_run(&source) {
+        Ok(doc) => doc,
+        Err(e) => {
+            println!("{} {}", "Error:".red(), e);
             return;
         }
     };
 
-    println!("{}: {}", criteria.name.green().bold(), criteria.description);
-    println!("Threshold: BMB/C <= {:.2}x for compute", criteria.threshold);
-    println!("Contract threshold: BMB/C <= {:.2}x (faster)", criteria.contract_threshold);
-    println!();
+    if let Err(e) = select_reports(&benches_dir, select, &mut doc.reports) {
+        println!("{} {}", "Error:".red(), e);
+        return;
+    }
 
-    let benches_dir = find_benches_dir();
-    let mut passed = 0;
-    let mut failed = 0;
-    let mut faster_count = 0;
-    let mut results = Vec::new();
+    let data = report::ReportData::from_run(&source.display().to_string(), &doc);
+
+    if update_readme {
+        let readme = benches_dir.parent().unwrap_or(Path::new(".")).join("README.md");
+        let updated = fs::read_to_string(&readme)
+            .map_err(|e| e.to_string())
+            .and_then(|text| report::update_marked_tables(&text, &data));
+        match updated {
+            Ok((text, count)) => match fs::write(&readme, text) {
+                Ok(()) => println!("{} Rewrote {} table(s) in {}", "Success:".green(), count, readme.display()),
+                Err(e) => println!("{} {}: {}", "Error:".red(), readme.display(), e),
+            },
+            Err(e) => println!("{} {}: {}", "Error:".red(), readme.display(), e),
+        }
+    }
 
-    // Run compute benchmarks
-    println!("{}", "Compute Benchmarks:".cyan());
-    println!("{}", "-".repeat(70));
-    println!("{:<20} {:>12} {:>12} {:>10} {:>10}", "Benchmark", "C (ms)", "BMB (ms)", "Ratio", "Status");
-    println!("{}", "-".repeat(70));
+    let (rendered, ext) = match format {
+        "json" => (report::render_json(&data), "json"),
+        "html" => (report::render_html(&data), "html"),
+        "markdown" => (report::render_markdown(&data), "md"),
+        _ => {
+            print!("{}", report::render_text(&data));
+            return;
+        }
+    };
+
+    let output_dir = output.map(Path::to_path_buf).unwrap_or(results_dir);
+    let stem = source
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "run".to_string());
+    let path = output_dir.join(format!("report-{}.{}", stem, ext));
+
+    match fs::create_dir_all(&output_dir).and_then(|_| fs::write(&path, rendered)) {
+        Ok(()) => println!("{} Wrote {}", "Success:".green(), path.display()),
+        Err(e) => println!("{} {}: {}", "Error:".red(), path.display(), e),
+    }
+}
 
-    for bench_name in criteria.benchmarks {
-        let bench_path = benches_dir.join("compute").join(bench_name);
-        if !bench_path.exists() {
-            if verbose {
-                println!("{:<20} {}", bench_name, "Not found".yellow());
+/// Relative change `diff` reports without a threshold or calibration.
+const DEFAULT_DIFF_THRESHOLD: f64 = 0.05;
+
+fn diff_results(
+    old: &Path,
+    new: &Path,
+    threshold: Option<f64>,
+    confidence: f64,
+    language: Option<&str>,
+    exit_code: i32,
+) {
+    let threshold = match threshold {
+        Some(percent) => percent / 100.0,
+        None => match calibrate::load_for_host(&results::results_dir(&find_benches_dir())) {
+            Some(calibration) => {
+                println!(
+                    "Using calibrated noise floor ±{:.1}% as threshold (calibrated {})",
+                    calibration.noise_floor * 100.0,
+                    calibration.timestamp.get(..10).unwrap_or(&calibration.timestamp)
+                );
+                calibration.noise_floor
             }
-            continue;
+            None => DEFAULT_DIFF_THRESHOLD,
+        },
+    };
+
+    let opts = diff::DiffOptions {
+        threshold,
+        confidence,
+        language,
+    };
+
+    match diff::diff_files(old, new, &opts) {
+        Ok(regressions) if regressions > 0 && exit_code != 0 => std::process::exit(exit_code),
+        Ok(_) => {}
+        Err(e) => {
+            println!("{} {}", "Error:".red(), e);
+            std::process::exit(2);
         }
+    }
+}
 
-        let c_times = run_language_benchmark(&bench_path.join("c"), "c", iterations, 2);
-        let bmb_times = run_language_benchmark(&bench_path.join("bmb"), "bmb", iterations, 2);
+/// Keeps the stored reports the selector picks. Tags come from the
+/// benchmark's current manifest, or just its recorded category if it no
+/// longer exists.
+fn select_reports(
+    benches_dir: &Path,
+    select: &select::SelectArgs,
+    reports: &mut Vec<ComparisonReport>,
+) -> Result<(), String> {
+    let selector = select::Selector::from_args(select, benches_dir)?;
+    let discovered = discover_benchmarks(benches_dir, None);
+    let known: Vec<&str> = discovered
+        .iter()
+        .map(|(n, _)| n.as_str())
+        .chain(reports.iter().map(|r| r.benchmark.as_str()))
+        .collect();
+    selector.check_names(&known)?;
+
+    reports.retain(|report| {
+        let category = report.results.first().map(|r| r.category.clone()).unwrap_or_default();
+        let tags = match discovered.iter().find(|(n, p)| *n == report.benchmark && manifest::category(p) == category) {
+            Some((_, path)) => manifest::tags(path),
+            None => vec![category.clone()],
+        };
+        select.category.as_ref().is_none_or(|c| *c == category) && selector.matches(&report.benchmark, &tags)
+    });
 
-        if let (Some(c_t), Some(bmb_t)) = (c_times, bmb_times) {
-            let c_median = median(&c_t);
-            let bmb_median = median(&bmb_t);
-            let ratio = bmb_median / c_median;
+    if reports.is_empty() {
+        return Err(format!("No stored results match '{}'.", select.describe()));
+    }
+    Ok(())
+}
 
-            let (status, symbol) = if ratio <= criteria.threshold {
-                passed += 1;
-                if ratio < 1.0 {
-                    faster_count += 1;
-                    ("PASS".green().to_string(), "✓★".green().to_string())
-                } else {
-                    ("PASS".green().to_string(), "✓".green().to_string())
-                }
-            } else {
-                failed += 1;
-                ("FAIL".red().to_string(), "✗".red().to_string())
-            };
+fn calibrate_noise(select: &select::SelectArgs, lang: &str, iterations: u32, warmup: u32, trials: u32) {
+    let benches_dir = find_benches_dir();
+    let Some(benchmarks) = selected_benchmarks(&benches_dir, select) else {
+        return;
+    };
+
+    calibrate::run(
+        &benchmarks,
+        &results::results_dir(&benches_dir),
+        &calibrate::CalibrateOptions {
+            language: lang,
+            iterations,
+            warmup,
+            trials,
+        },
+    );
+}
+
+fn fuzz_bootstrap(
+    target: &str,
+    cases: u32,
+    mode: &str,
+    seed: Option<u64>,
+    max_len: usize,
+    shrink_steps: u32,
+) {
+    println!("{}", "=== BMB Bootstrap Differential Fuzzing ===".cyan().bold());
+    println!();
+
+    let targets: Vec<&str> = if target == "all" {
+        fuzz::TARGETS.to_vec()
+    } else if fuzz::TARGETS.contains(&target) {
+        vec![target]
+    } else {
+        println!(
+            "{} Unknown fuzz target: {}. Valid: {}, all",
+            "Error:".red(),
+            target,
+            fuzz::TARGETS.join(", ")
+        );
+        return;
+    };
+
+    let Some(mode) = fuzz::Mode::parse(mode) else {
+        println!("{} Unknown mode: {}. Valid: random, grammar, mixed", "Error:".red(), mode);
+        return;
+    };
+
+    if max_len > fuzz::MAX_INPUT_LEN {
+        println!(
+            "{} --max-len capped at {} bytes (C harness buffer)",
+            "Warning:".yellow(),
+            fuzz::MAX_INPUT_LEN
+        );
+    }
+
+    let opts = fuzz::FuzzOptions {
+        cases,
+        mode,
+        seed: seed.unwrap_or_else(rng::Rng::time_seed),
+        max_len,
+        shrink_steps,
+    };
 
-            println!("{:<20} {:>12.2} {:>12.2} {:>10.2}x {:>10}",
-                bench_name, c_median, bmb_median, ratio, symbol);
+    let benches_dir = find_benches_dir();
+    let mut divergent = 0;
+    for target in targets {
+        divergent += fuzz::run(target, &benches_dir, &opts);
+        println!();
+    }
 
-            results.push((bench_name.to_string(), ratio, status));
+    if divergent > 0 {
+        std::process::exit(1);
+    }
+}
+
+fn show_history(benchmark: Option<&str>, language: Option<&str>, session: Option<&str>, limit: usize) {
+    let results_dir = results::results_dir(&find_benches_dir());
+    match history::load(&results_dir) {
+        Ok(records) => history::show(
+            &records,
+            &history::Query {
+                benchmark,
+                language,
+                session,
+                limit,
+            },
+        ),
+        Err(e) => {
+            println!("{} {}", "Error:".red(), e);
+            std::process::exit(2);
         }
     }
+}
+
+fn import_results(files: &[PathBuf], overrides: import::Overrides, dry_run: bool) {
+    let benches_dir = find_benches_dir();
+    let results_dir = results::results_dir(&benches_dir);
+
+    let files: Vec<PathBuf> = if files.is_empty() {
+        let mut found: Vec<PathBuf> = fs::read_dir(&results_dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "md" || e == "csv"))
+            .filter(|p| !p.file_name().is_some_and(|n| n.to_string_lossy().starts_with("report-")))
+            .collect();
+        found.sort();
+        found
+    } else {
+        files.to_vec()
+    };
 
-    // Run contract benchmarks
+    let known: std::collections::BTreeMap<String, String> = discover_benchmarks(&benches_dir, None)
+        .into_iter()
+        .map(|(name, path)| {
+            let category = manifest::category(&path);
+            (name, category)
+        })
+        .collect();
+
+    let history = match history::load(&results_dir) {
+        Ok(history) => history,
+        Err(e) => {
+            println!("{} {}", "Error:".red(), e);
+            std::process::exit(2);
+        }
+    };
+
+    println!("{}", "=== Import Legacy Results ===".cyan().bold());
     println!();
-    println!("{}", "Contract Benchmarks:".cyan());
-    println!("{}", "-".repeat(70));
 
-    for bench_name in &["bounds_check", "null_check", "purity_opt", "aliasing"] {
-        let bench_path = benches_dir.join("contract").join(bench_name);
-        if !bench_path.exists() {
+    let mut imported = Vec::new();
+    for file in &files {
+        let file_name = file.file_name().unwrap_or_default().to_string_lossy().to_string();
+        if history.iter().any(|r| r.source.as_deref() == Some(file_name.as_str())) {
+            println!("  {:<44} {}", file_name, "already imported".yellow());
             continue;
         }
 
-        let c_times = run_language_benchmark(&bench_path.join("c"), "c", iterations, 2);
-        let bmb_times = run_language_benchmark(&bench_path.join("bmb"), "bmb", iterations, 2);
+        match import::import_file(file, &known, &overrides) {
+            Ok(result) if result.records.is_empty() => {
+                println!("  {:<44} {}", file_name, "no results found".yellow());
+                print_skipped(&result);
+            }
+            Ok(result) => {
+                let first = &result.records[0];
+                let samples: usize = result.records.iter().map(|r| r.samples.len()).sum();
+                let gates = result.records.iter().filter(|r| r.gate.is_some()).count();
+                println!(
+                    "  {:<44} {} {:<10} {:>3} samples, {} gate verdicts",
+                    file_name,
+                    first.timestamp,
+                    first.bmb_version.as_deref().unwrap_or("-"),
+                    samples,
+                    gates
+                );
+                print_skipped(&result);
+                imported.extend(result.records);
+            }
+            Err(e) => println!("  {} {}", "Error:".red(), e),
+        }
+    }
+
+    println!();
+    if dry_run {
+        println!("Dry run: {} record(s) not written", imported.len());
+    } else if !imported.is_empty() {
+        match history::append(&results_dir, &imported) {
+            Ok(()) => println!(
+                "{} Appended {} record(s) to {}",
+                "Success:".green(),
+                imported.len(),
+                history::history_path(&results_dir).display()
+            ),
+            Err(e) => println!("{} {}", "Error:".red(), e),
+        }
+    }
+}
 
-        if let (Some(c_t), Some(bmb_t)) = (c_times, bmb_times) {
-            let c_median = median(&c_t);
-            let bmb_median = median(&bmb_t);
-            let ratio = bmb_median / c_median;
+fn print_skipped(result: &import::Imported) {
+    if result.skipped_tables > 0 {
+        println!(
+            "    {} {} `Language | Time (ms)` table(s) skipped: this layout is not supported",
+            "Note:".yellow(),
+            result.skipped_tables
+        );
+    }
+    if !result.unmatched.is_empty() {
+        println!(
+            "    {} skipped, not a benchmark under benches/: {}",
+            "Note:".yellow(),
+            result.unmatched.join(", ")
+        );
+    }
+}
 
-            let (status, symbol) = if ratio <= criteria.contract_threshold {
-                passed += 1;
-                faster_count += 1;
-                ("PASS".green().to_string(), "✓★".green().to_string())
-            } else if ratio <= 1.0 {
-                passed += 1;
-                ("PASS".green().to_string(), "✓".green().to_string())
-            } else {
-                failed += 1;
-                ("FAIL".red().to_string(), "✗".red().to_string())
-            };
+/// Where `gate --format` writes machine-readable results
+struct GateOutput {
+    format: String,
+    path: Option<PathBuf>,
+}
 
-            println!("{:<20} {:>12.2} {:>12.2} {:>10.2}x {:>10}",
-                bench_name, c_median, bmb_median, ratio, symbol);
-        }
+fn verify_gate(
+    gate_id: &str,
+    sampling: &gate::Sampling,
+    output: &GateOutput,
+    against: Option<&str>,
+    verbose: bool,
+    session: Option<String>,
+    strict: bool,
+) {
+    let format = output.format.as_str();
+    if !gate_report::FORMATS.contains(&format) {
+        println!(
+            "{} Unknown format: {}. Valid: {}",
+            "Error:".red(),
+            format,
+            gate_report::FORMATS.join(", ")
+        );
+        std::process::exit(2);
     }
 
-    // Summary
+    println!("{}", "=== BMB Benchmark Gate Verification ===".cyan().bold());
     println!();
-    println!("{}", "=".repeat(70));
-    println!("{}: {} passed, {} failed, {} faster than C",
-        "Summary".cyan().bold(),
-        passed.to_string().green(),
-        if failed > 0 { failed.to_string().red() } else { failed.to_string().green() },
-        faster_count.to_string().cyan()
-    );
 
-    let gate_passed = failed == 0;
-    if gate_passed {
+    let benches_dir = find_benches_dir();
+    let gates_path = gate::gates_path(&benches_dir);
+    let mut gates = match gate::GateFile::load(&gates_path) {
+        Ok(gates) => gates,
+        Err(e) => {
+            println!("{} {}", "Error:".red(), e);
+            std::process::exit(2);
+        }
+    };
+    let Some(criteria) = gates.find(gate_id) else {
+        println!(
+            "{} Unknown gate: {}. Valid: {}",
+            "Error:".red(),
+            gate_id,
+            gates.ids().join(", ")
+        );
+        std::process::exit(2);
+    };
+
+    let selected = match criteria.resolve(&benches_dir) {
+        Ok(selected) => selected,
+        Err(e) => {
+            println!("{} {}: {}", "Error:".red(), gates_path.display(), e);
+            std::process::exit(2);
+        }
+    };
+
+    let pinned = match (criteria.kind, against.or(criteria.against.as_deref())) {
+        (gate::GateKind::Regression, Some(selector)) => {
+            match history::find_run(&results::results_dir(&benches_dir), selector) {
+                Ok((source, doc)) => Some(gate::Pinned { source, doc }),
+                Err(e) => {
+                    println!("{} {}", "Error:".red(), e);
+                    std::process::exit(2);
+                }
+            }
+        }
+        (gate::GateKind::Ratio, Some(_)) if against.is_some() => {
+            println!("{} --against applies only to regression gates", "Error:".red());
+            std::process::exit(2);
+        }
+        _ => None,
+    };
+
+    // A regression gate tolerates at least the calibrated noise; a ratio
+    // gate's limits are targets and are only checked against it
+    let calibration = calibrate::load_for_host(&results::results_dir(&benches_dir));
+    let raised = match (&calibration, criteria.kind) {
+        (Some(calibration), gate::GateKind::Regression) => criteria.apply_noise_floor(calibration.noise_floor),
+        _ => false,
+    };
+    let criteria = &*criteria;
+
+    gate::print_header(criteria);
+
+    if let Some(run) = &pinned {
+        println!(
+            "Pinned run: {} ({}, session {}, revision {})",
+            run.source.display(),
+            run.doc.timestamp,
+            run.doc.session.as_deref().unwrap_or("-"),
+            run.doc.git_revision.as_deref().unwrap_or("-")
+        );
+        let here = host::HostInfo::detect();
+        if let Some(there) = run.doc.host.as_ref().filter(|h| h.hostname != here.hostname || h.cpu_model != here.cpu_model) {
+            println!(
+                "{} pinned run was measured on {}, not this machine; ratios include the hardware difference",
+                "Warning:".yellow(),
+                there.summary()
+            );
+        }
         println!();
-        println!("{} {} {}", "✓".green().bold(), criteria.name.green().bold(), "PASSED".green().bold());
-    } else {
+    }
+
+    if let Some(calibration) = &calibration {
+        println!("Noise floor: ±{:.1}% (calibrated)", calibration.noise_floor * 100.0);
+        if raised {
+            println!(
+                "Limits below {:.3}x were raised to it; smaller slowdowns are within the noise",
+                1.0 + calibration.noise_floor
+            );
+        }
+        if let Some(margin) = criteria
+            .tightest_margin()
+            .filter(|margin| criteria.kind == gate::GateKind::Ratio && calibration.noise_floor >= *margin)
+        {
+            println!(
+                "{} the noise floor is not below this gate's {:.0}% margin; single results cannot resolve it",
+                "Warning:".yellow(),
+                margin * 100.0
+            );
+        }
         println!();
-        println!("{} {} {}", "✗".red().bold(), criteria.name.red().bold(), "FAILED".red().bold());
-        std::process::exit(1);
+    }
+
+    let preflight_warnings = preflight::check(strict);
+
+    let mut outcome = gate::verify(criteria, &selected, sampling, pinned.as_ref(), verbose);
+
+    let mut doc = results::RunDocument::new("gate", std::mem::take(&mut outcome.reports));
+    doc.session = session;
+    doc.preflight_warnings = preflight_warnings;
+    doc.gate = Some(results::GateRecord {
+        name: criteria.name.clone(),
+        passed: outcome.verdict == gate::Verdict::Pass,
+        inconclusive: outcome.verdict == gate::Verdict::Inconclusive,
+        passed_count: outcome.passed_count,
+        failed_count: outcome.failed_count,
+        inconclusive_count: outcome.inconclusive_count,
+        excluded_count: outcome.excluded_count,
+    });
+    let rendered = {
+        let report = gate_report::GateReport::new(criteria, &outcome, &doc, pinned.as_ref(), sampling.confidence);
+        match format {
+            "json" => Some((gate_report::render_json(&report), "json")),
+            "junit" => Some((gate_report::render_junit(&report), "xml")),
+            _ => None,
+        }
+    };
+    println!();
+    save_run(&benches_dir, doc);
+
+    if let Some((text, ext)) = rendered {
+        let path = output.path.clone().unwrap_or_else(|| {
+            results::results_dir(&benches_dir).join(format!("gate-{}.{}", criteria.id, ext))
+        });
+        let written = match path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            Some(dir) => fs::create_dir_all(dir).and_then(|_| fs::write(&path, text)),
+            None => fs::write(&path, text),
+        };
+        match written {
+            Ok(()) => println!("{} Wrote {}", "Success:".green(), path.display()),
+            Err(e) => println!("{} {}: {}", "Error:".red(), path.display(), e),
+        }
+    }
+
+    println!();
+    match outcome.verdict {
+        gate::Verdict::Pass => {
+            println!("{} {} {}", "✓".green().bold(), criteria.name.green().bold(), "PASSED".green().bold());
+        }
+        gate::Verdict::Fail => {
+            println!("{} {} {}", "✗".red().bold(), criteria.name.red().bold(), "FAILED".red().bold());
+            std::process::exit(1);
+        }
+        gate::Verdict::Inconclusive => {
+            println!(
+                "{} {} {} (rerun with more --iterations or a larger --budget)",
+                "?".yellow().bold(),
+                criteria.name.yellow().bold(),
+                "INCONCLUSIVE".yellow().bold()
+            );
+            std::process::exit(3);
+        }
     }
 }
 
@@ -677,19 +1756,3 @@ fn discover_benchmarks(base: &Path, category: Option<&str>) -> Vec<(String, Path
     benchmarks.sort_by(|a, b| a.0.cmp(&b.0));
     benchmarks
 }
-
-fn median(values: &[f64]) -> f64 {
-    if values.is_empty() {
-        return 0.0;
-    }
-
-    let mut sorted = values.to_vec();
-    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
-
-    let mid = sorted.len() / 2;
-    if sorted.len() % 2 == 0 {
-        (sorted[mid - 1] + sorted[mid]) / 2.0
-    } else {
-        sorted[mid]
-    }
-}
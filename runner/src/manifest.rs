@@ -0,0 +1,73 @@
+//! Per-benchmark metadata from an optional `bench.toml`.
+//!
+//! ```toml
+//! description = "Naive recursive Fibonacci"
+//! input_size = "fib(35)"
+//! tags = ["benchmarks-game", "recursion"]
+//!
+//! [variants]
+//! bmb = ["fp"]
+//! ```
+//!
+//! A benchmark's category is always one of its tags, so selectors such as
+//! `tags = ["contract"]` work without a manifest.
+//!
+//! `variants` names the alternative sources (`bmb/main_fp.bmb`) measured
+//! beside each language's `main`. A language the table leaves out has every
+//! `main_<variant>` source in its directory measured.
+
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+pub const MANIFEST_FILE: &str = "bench.toml";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    /// One line shown by `list`
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Problem size the implementations hard-code, for `list`
+    #[serde(default)]
+    pub input_size: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Language to the variants measured for it
+    #[serde(default)]
+    pub variants: BTreeMap<String, Vec<String>>,
+}
+
+impl Manifest {
+    /// Manifest of the benchmark at `bench_path`; empty if there is none.
+    pub fn load(bench_path: &Path) -> Result<Self, String> {
+        let path = bench_path.join(MANIFEST_FILE);
+        match fs::read_to_string(&path) {
+            Ok(text) => toml::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Manifest::default()),
+            Err(e) => Err(format!("{}: {}", path.display(), e)),
+        }
+    }
+}
+
+/// Category of a benchmark directory (`benches/<category>/<name>`).
+pub fn category(bench_path: &Path) -> String {
+    bench_path
+        .parent()
+        .and_then(|p| p.file_name())
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Manifest tags plus the category. A malformed manifest contributes no
+/// tags; commands that read the full manifest report the error.
+pub fn tags(bench_path: &Path) -> Vec<String> {
+    let mut tags = Manifest::load(bench_path).map(|m| m.tags).unwrap_or_default();
+    let category = category(bench_path);
+    if !tags.contains(&category) {
+        tags.push(category);
+    }
+    tags
+}
@@ -0,0 +1,214 @@
+//! Environment noise checks run before measuring.
+//!
+//! A 5% gate margin is well inside the noise of a loaded machine or one
+//! whose clock speed changes under it, so `run` and `gate` first look at
+//! load, frequency scaling, turbo, competing processes and free memory.
+//! Checks that cannot be read on this platform are skipped.
+
+use colored::Colorize;
+use std::fs;
+use std::thread;
+use std::time::Duration;
+
+/// 1-minute load average above which other work competes for the CPU.
+const MAX_LOAD: f64 = 1.0;
+
+/// Share of one CPU another process may use while we measure.
+const MAX_PROCESS_CPU: f64 = 0.10;
+
+/// Window over which process CPU usage is sampled.
+const SAMPLE_WINDOW: Duration = Duration::from_millis(250);
+
+/// Minimum available memory, in MiB.
+const MIN_AVAILABLE_MB: u64 = 1024;
+
+struct Finding {
+    check: &'static str,
+    /// None when the check could not be performed here
+    ok: Option<bool>,
+    detail: String,
+}
+
+/// Run every check and print the outcome. Returns the warnings so they can
+/// be stored with the results. With `strict`, any warning aborts the
+/// process with exit code 2.
+pub fn check(strict: bool) -> Vec<String> {
+    let findings = [load(), governor(), turbo(), busy_processes(), memory()];
+
+    println!("{}", "Preflight:".cyan());
+    let mut warnings = Vec::new();
+    for finding in &findings {
+        let symbol = match finding.ok {
+            Some(true) => "✓".green().to_string(),
+            Some(false) => "⚠".yellow().to_string(),
+            None => "-".dimmed().to_string(),
+        };
+        println!("  {} {:<12} {}", symbol, finding.check, finding.detail);
+        if finding.ok == Some(false) {
+            warnings.push(format!("{}: {}", finding.check, finding.detail));
+        }
+    }
+    println!();
+
+    if !warnings.is_empty() {
+        if strict {
+            println!(
+                "{} {} preflight warning(s); refusing to measure under --strict",
+                "Error:".red(),
+                warnings.len()
+            );
+            std::process::exit(2);
+        }
+        println!(
+            "{} ratios may be unreliable; use --strict to refuse noisy environments",
+            "Warning:".yellow()
+        );
+        println!();
+    }
+
+    warnings
+}
+
+fn load() -> Finding {
+    let load = fs::read_to_string("/proc/loadavg")
+        .ok()
+        .and_then(|s| s.split_whitespace().next()?.parse::<f64>().ok());
+    match load {
+        Some(load) => Finding {
+            check: "load",
+            ok: Some(load <= MAX_LOAD),
+            detail: format!("1-minute load average {:.2} (max {:.1})", load, MAX_LOAD),
+        },
+        None => unavailable("load"),
+    }
+}
+
+fn governor() -> Finding {
+    let mut governors: Vec<String> = fs::read_dir("/sys/devices/system/cpu")
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| fs::read_to_string(e.path().join("cpufreq/scaling_governor")).ok())
+        .map(|g| g.trim().to_string())
+        .collect();
+    governors.sort();
+    governors.dedup();
+
+    if governors.is_empty() {
+        return unavailable("governor");
+    }
+    Finding {
+        check: "governor",
+        ok: Some(governors.iter().all(|g| g == "performance")),
+        detail: format!("scaling governor {} (want performance)", governors.join("/")),
+    }
+}
+
+fn turbo() -> Finding {
+    // intel_pstate exposes "no_turbo"; acpi-cpufreq and amd-pstate "boost"
+    let enabled = fs::read_to_string("/sys/devices/system/cpu/intel_pstate/no_turbo")
+        .ok()
+        .map(|v| v.trim() == "0")
+        .or_else(|| {
+            fs::read_to_string("/sys/devices/system/cpu/cpufreq/boost")
+                .ok()
+                .map(|v| v.trim() == "1")
+        });
+    match enabled {
+        Some(enabled) => Finding {
+            check: "turbo",
+            ok: Some(!enabled),
+            detail: if enabled {
+                "turbo/boost enabled; clock speed varies with temperature".to_string()
+            } else {
+                "turbo/boost disabled".to_string()
+            },
+        },
+        None => unavailable("turbo"),
+    }
+}
+
+fn busy_processes() -> Finding {
+    let Some(before) = process_times() else {
+        return unavailable("processes");
+    };
+    thread::sleep(SAMPLE_WINDOW);
+    let Some(after) = process_times() else {
+        return unavailable("processes");
+    };
+
+    let ticks_per_sec = 100.0; // USER_HZ on every Linux ABI we run on
+    let window_ticks = SAMPLE_WINDOW.as_secs_f64() * ticks_per_sec;
+    let own_pid = std::process::id();
+
+    let mut busy: Vec<(String, f64)> = after
+        .iter()
+        .filter(|(pid, _, _)| *pid != own_pid)
+        .filter_map(|(pid, name, ticks)| {
+            let (_, _, old) = before.iter().find(|(p, _, _)| p == pid)?;
+            let share = ticks.saturating_sub(*old) as f64 / window_ticks;
+            (share > MAX_PROCESS_CPU).then(|| (name.clone(), share))
+        })
+        .collect();
+    busy.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    Finding {
+        check: "processes",
+        ok: Some(busy.is_empty()),
+        detail: if busy.is_empty() {
+            "no other busy processes".to_string()
+        } else {
+            let names: Vec<String> = busy
+                .iter()
+                .take(5)
+                .map(|(name, share)| format!("{} {:.0}%", name, share * 100.0))
+                .collect();
+            format!("busy: {}", names.join(", "))
+        },
+    }
+}
+
+/// (pid, command name, user + system ticks) of every process.
+fn process_times() -> Option<Vec<(u32, String, u64)>> {
+    let entries = fs::read_dir("/proc").ok()?;
+    let times = entries
+        .flatten()
+        .filter_map(|e| {
+            let pid: u32 = e.file_name().to_str()?.parse().ok()?;
+            let stat = fs::read_to_string(e.path().join("stat")).ok()?;
+            // The command name is parenthesized and may contain spaces
+            let open = stat.find('(')?;
+            let close = stat.rfind(')')?;
+            let name = stat[open + 1..close].to_string();
+            let fields: Vec<&str> = stat[close + 2..].split_whitespace().collect();
+            let utime: u64 = fields.get(11)?.parse().ok()?;
+            let stime: u64 = fields.get(12)?.parse().ok()?;
+            Some((pid, name, utime + stime))
+        })
+        .collect();
+    Some(times)
+}
+
+fn memory() -> Finding {
+    let available_kb = fs::read_to_string("/proc/meminfo").ok().and_then(|text| {
+        text.lines()
+            .find_map(|l| l.strip_prefix("MemAvailable:"))
+            .and_then(|v| v.split_whitespace().next()?.parse::<u64>().ok())
+    });
+    match available_kb {
+        Some(kb) => Finding {
+            check: "memory",
+            ok: Some(kb / 1024 >= MIN_AVAILABLE_MB),
+            detail: format!("{} MiB available (min {})", kb / 1024, MIN_AVAILABLE_MB),
+        },
+        None => unavailable("memory"),
+    }
+}
+
+fn unavailable(check: &'static str) -> Finding {
+    Finding {
+        check,
+        ok: None,
+        detail: "not available on this system".to_string(),
+    }
+}
@@ -0,0 +1,651 @@
+//! Rendering of stored runs into text, JSON, HTML and markdown reports,
+//! and of the generated tables in README.md.
+
+use crate::host::HostInfo;
+use crate::results::RunDocument;
+use crate::ComparisonReport;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+/// Flattened view of a run, shared by every output format.
+#[derive(Debug, Serialize)]
+pub struct ReportData {
+    pub source: String,
+    pub timestamp: String,
+    pub languages: Vec<String>,
+    pub categories: BTreeMap<String, Vec<Row>>,
+    pub host: Option<HostInfo>,
+    pub toolchains: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Row {
+    pub benchmark: String,
+    pub baseline: String,
+    pub cells: Vec<Cell>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Cell {
+    pub language: String,
+    pub median_ms: f64,
+    pub min_ms: f64,
+    pub max_ms: f64,
+    /// Median relative to the baseline language, if it was measured
+    pub ratio: Option<f64>,
+}
+
+impl ReportData {
+    pub fn from_run(source: &str, doc: &RunDocument) -> Self {
+        let mut data = Self::from_reports(source, &doc.reports);
+        data.host = doc.host.clone();
+        data.toolchains = doc.toolchains.clone();
+        data
+    }
+
+    pub fn from_reports(source: &str, reports: &[ComparisonReport]) -> Self {
+        let mut languages: Vec<String> = Vec::new();
+        let mut categories: BTreeMap<String, Vec<Row>> = BTreeMap::new();
+
+        for report in reports {
+            let baseline_ms = report
+                .results
+                .iter()
+                .find(|r| r.language == report.baseline)
+                .map(|r| r.median_ms);
+
+            let mut cells: Vec<Cell> = report
+                .results
+                .iter()
+                .map(|r| Cell {
+                    language: r.language.clone(),
+                    median_ms: r.median_ms,
+                    min_ms: r.min_ms,
+                    max_ms: r.max_ms,
+                    ratio: baseline_ms.map(|b| r.median_ms / b),
+                })
+                .collect();
+            cells.sort_by_key(|c| (language_rank(&c.language), c.language.clone()));
+
+            for cell in &cells {
+                if !languages.contains(&cell.language) {
+                    languages.push(cell.language.clone());
+                }
+            }
+
+            let category = report
+                .results
+                .first()
+                .map(|r| r.category.clone())
+                .unwrap_or_default();
+            categories.entry(category).or_default().push(Row {
+                benchmark: report.benchmark.clone(),
+                baseline: report.baseline.clone(),
+                cells,
+            });
+        }
+
+        // Baseline-first ordering reads naturally in every table
+        languages.sort_by_key(|l| (language_rank(l), l.clone()));
+
+        let timestamp = reports
+            .iter()
+            .map(|r| r.timestamp.clone())
+            .max()
+            .unwrap_or_default();
+
+        ReportData {
+            source: source.to_string(),
+            timestamp,
+            languages,
+            categories,
+            host: None,
+            toolchains: BTreeMap::new(),
+        }
+    }
+
+    /// "gcc: gcc (GCC) 13.2.0; rustc: rustc 1.84.0" list of recorded tools.
+    fn toolchain_summary(&self) -> String {
+        self.toolchains
+            .iter()
+            .map(|(tool, version)| format!("{}: {}", tool, version))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    /// Languages that appear as the non-baseline side of some ratio.
+    fn ratio_languages(&self) -> Vec<&String> {
+        self.languages
+            .iter()
+            .filter(|l| {
+                self.categories
+                    .values()
+                    .flatten()
+                    .any(|row| &row.baseline != *l && row.cell(l).is_some_and(|c| c.ratio.is_some()))
+            })
+            .collect()
+    }
+
+    /// The language whose ratio the markdown tables report: BMB when it
+    /// was measured, otherwise the last non-baseline language.
+    fn subject_language(&self) -> &str {
+        let ratio_langs = self.ratio_languages();
+        if ratio_langs.iter().any(|l| *l == "bmb") {
+            return "bmb";
+        }
+        ratio_langs.last().map(|l| l.as_str()).unwrap_or("bmb")
+    }
+
+    fn baseline_label(&self) -> String {
+        let mut baselines: Vec<&str> = self
+            .categories
+            .values()
+            .flatten()
+            .map(|r| r.baseline.as_str())
+            .collect();
+        baselines.sort();
+        baselines.dedup();
+        baselines.join("/").to_uppercase()
+    }
+}
+
+impl Row {
+    fn cell(&self, language: &str) -> Option<&Cell> {
+        self.cells.iter().find(|c| c.language == language)
+    }
+
+    fn subject_ratio(&self, subject: &str) -> Option<f64> {
+        if self.baseline == subject {
+            return None;
+        }
+        self.cell(subject).and_then(|c| c.ratio)
+    }
+}
+
+/// Baseline-first order; variants (`bmb:fp`) sort with their language.
+pub fn language_rank(lang: &str) -> usize {
+    match lang.split(':').next().unwrap_or(lang) {
+        "c" => 0,
+        "rust" => 1,
+        "bmb" => 2,
+        _ => 3,
+    }
+}
+
+pub fn render_text(data: &ReportData) -> String {
+    let mut out = String::new();
+    let ratio_langs = data.ratio_languages();
+
+    let _ = writeln!(out, "BMB Benchmark Report");
+    let _ = writeln!(out, "Source: {}", data.source);
+    let _ = writeln!(out, "Timestamp: {}", data.timestamp);
+    if let Some(host) = &data.host {
+        let _ = writeln!(out, "Host: {}", host.summary());
+    }
+    if !data.toolchains.is_empty() {
+        let _ = writeln!(out, "Toolchains: {}", data.toolchain_summary());
+    }
+
+    for (category, rows) in &data.categories {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "Category: {}", category);
+
+        // Columns widen for long variant labels such as BMB:NOHEAP
+        let time_labels: Vec<String> = data.languages.iter().map(|l| format!("{} (ms)", l.to_uppercase())).collect();
+        let ratio_labels: Vec<String> = ratio_langs.iter().map(|l| format!("{}/base", l.to_uppercase())).collect();
+        let time_widths: Vec<usize> = time_labels.iter().map(|l| l.len().max(12)).collect();
+        let ratio_widths: Vec<usize> = ratio_labels.iter().map(|l| l.len().max(10)).collect();
+
+        let mut header = format!("{:<20}", "Benchmark");
+        for (label, width) in time_labels.iter().zip(&time_widths) {
+            let _ = write!(header, " {:>width$}", label, width = width);
+        }
+        for (label, width) in ratio_labels.iter().zip(&ratio_widths) {
+            let _ = write!(header, " {:>width$}", label, width = width);
+        }
+        let rule = "-".repeat(header.len());
+        let _ = writeln!(out, "{}", rule);
+        let _ = writeln!(out, "{}", header);
+        let _ = writeln!(out, "{}", rule);
+
+        for row in rows {
+            let _ = write!(out, "{:<20}", row.benchmark);
+            for (lang, width) in data.languages.iter().zip(&time_widths) {
+                match row.cell(lang) {
+                    Some(cell) => {
+                        let _ = write!(out, " {:>width$.2}", cell.median_ms, width = width);
+                    }
+                    None => {
+                        let _ = write!(out, " {:>width$}", "-", width = width);
+                    }
+                }
+            }
+            for (lang, width) in ratio_langs.iter().zip(&ratio_widths) {
+                match row.cell(lang).and_then(|c| c.ratio) {
+                    Some(ratio) if &&row.baseline != lang => {
+                        let _ = write!(out, " {:>width$.2}x", ratio, width = width - 1);
+                    }
+                    _ => {
+                        let _ = write!(out, " {:>width$}", "-", width = width);
+                    }
+                }
+            }
+            let _ = writeln!(out);
+        }
+        let _ = writeln!(out, "{}", rule);
+    }
+
+    out
+}
+
+pub fn render_json(data: &ReportData) -> String {
+    serde_json::to_string_pretty(data).unwrap_or_default()
+}
+
+pub fn render_html(data: &ReportData) -> String {
+    let mut out = String::new();
+    let ratio_langs = data.ratio_languages();
+
+    let _ = write!(
+        out,
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>BMB Benchmark Report</title>
+<style>
+body {{ font-family: system-ui, sans-serif; margin: 2em; color: #222; }}
+table {{ border-collapse: collapse; margin: 1em 0; }}
+th, td {{ border: 1px solid #ccc; padding: 4px 10px; text-align: right; }}
+th {{ background: #f0f0f0; cursor: pointer; user-select: none; }}
+th:first-child, td:first-child {{ text-align: left; }}
+th.asc::after {{ content: " \25B2"; }}
+th.desc::after {{ content: " \25BC"; }}
+.faster {{ color: #1a7f37; }}
+.slower {{ color: #cf222e; }}
+.meta {{ color: #666; }}
+</style>
+</head>
+<body>
+<h1>BMB Benchmark Report</h1>
+<p class="meta">Source: {}<br>Timestamp: {}<br>{}Ratios are relative to the {} baseline; lower is faster.</p>
+"#,
+        escape(&data.source),
+        escape(&data.timestamp),
+        environment_html(data),
+        escape(&data.baseline_label())
+    );
+
+    for (category, rows) in &data.categories {
+        let _ = writeln!(out, "<h2>{}</h2>", escape(category));
+        let _ = writeln!(out, "<table class=\"sortable\">\n<thead><tr><th>Benchmark</th>");
+        for lang in &data.languages {
+            let _ = write!(out, "<th>{} (ms)</th>", escape(&lang.to_uppercase()));
+        }
+        for lang in &ratio_langs {
+            let _ = write!(out, "<th>{}/base</th>", escape(&lang.to_uppercase()));
+        }
+        let _ = writeln!(out, "</tr></thead>\n<tbody>");
+
+        for row in rows {
+            let _ = write!(out, "<tr><td>{}</td>", escape(&row.benchmark));
+            for lang in &data.languages {
+                match row.cell(lang) {
+                    Some(cell) => {
+                        let _ = write!(
+                            out,
+                            "<td data-value=\"{}\" title=\"min {:.2} / max {:.2}\">{:.2}</td>",
+                            cell.median_ms, cell.min_ms, cell.max_ms, cell.median_ms
+                        );
+                    }
+                    None => out.push_str("<td data-value=\"\">-</td>"),
+                }
+            }
+            for lang in &ratio_langs {
+                match row.cell(lang).and_then(|c| c.ratio) {
+                    Some(ratio) if &&row.baseline != lang => {
+                        let class = if ratio <= 1.0 { "faster" } else { "slower" };
+                        let _ = write!(
+                            out,
+                            "<td data-value=\"{}\" class=\"{}\">{:.2}x</td>",
+                            ratio, class, ratio
+                        );
+                    }
+                    _ => out.push_str("<td data-value=\"\">-</td>"),
+                }
+            }
+            let _ = writeln!(out, "</tr>");
+        }
+        let _ = writeln!(out, "</tbody>\n</table>");
+
+        out.push_str(&ratio_chart(rows, &ratio_langs));
+    }
+
+    out.push_str(SORT_SCRIPT);
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// Host and toolchain lines of the HTML header, each ending in `<br>`.
+fn environment_html(data: &ReportData) -> String {
+    let mut out = String::new();
+    if let Some(host) = &data.host {
+        let _ = write!(out, "Host: {}<br>", escape(&host.summary()));
+    }
+    if !data.toolchains.is_empty() {
+        let _ = write!(out, "Toolchains: {}<br>", escape(&data.toolchain_summary()));
+    }
+    out
+}
+
+/// Ratio up to which a result counts as parity with the baseline.
+const PARITY_RATIO: f64 = 1.05;
+
+/// Ratio beyond which a slowdown is reported as a failure.
+const SLOW_RATIO: f64 = 2.0;
+
+/// Start/end markers of a generated table in README.md. The start marker
+/// names the category to render, or `all` for every category.
+const TABLE_START: &str = "<!-- bench-table:";
+const TABLE_END: &str = "<!-- /bench-table -->";
+
+/// Markdown report in the layout of the hand-written `results/*.md` files.
+pub fn render_markdown(data: &ReportData) -> String {
+    let mut out = String::new();
+    let date = data.timestamp.get(..10).unwrap_or(&data.timestamp);
+    let subject = data.subject_language();
+
+    let _ = writeln!(out, "# BMB Benchmark Results - {}", date);
+    let _ = writeln!(out);
+    let _ = writeln!(out, "> Date: {}", date);
+    let _ = writeln!(out, "> Source: {}", data.source);
+    let _ = writeln!(out, "> Baseline: {}", data.baseline_label());
+    if let Some(host) = &data.host {
+        let _ = writeln!(out, "> System: {}", host.summary());
+    }
+    if !data.toolchains.is_empty() {
+        let _ = writeln!(out, "> Toolchains: {}", data.toolchain_summary());
+    }
+    let _ = writeln!(out);
+
+    let _ = writeln!(out, "## Executive Summary");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "| Category | Benchmarks Passed | Status |");
+    let _ = writeln!(out, "|----------|-------------------|--------|");
+    for (category, rows) in &data.categories {
+        let ratios: Vec<f64> = rows.iter().filter_map(|r| r.subject_ratio(subject)).collect();
+        let passed = ratios.iter().filter(|r| **r <= PARITY_RATIO).count();
+        let status = if ratios.is_empty() {
+            "📋 Not tested"
+        } else if passed == ratios.len() {
+            "✅ PASS"
+        } else if ratios.iter().any(|r| *r > SLOW_RATIO) {
+            "❌ SLOWER"
+        } else {
+            "⚠️ SLOWER"
+        };
+        let _ = writeln!(
+            out,
+            "| {} | {}/{} valid | {} |",
+            category_title(category),
+            passed,
+            ratios.len(),
+            status
+        );
+    }
+    let _ = writeln!(out);
+    let _ = writeln!(out, "---");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "## Detailed Results");
+
+    for (index, (category, rows)) in data.categories.iter().enumerate() {
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "### Category {}: {}",
+            (b'A' + index as u8) as char,
+            category_title(category)
+        );
+        let _ = writeln!(out);
+        out.push_str(&markdown_table(data, rows));
+
+        let missing: Vec<&str> = rows
+            .iter()
+            .filter(|r| r.subject_ratio(subject).is_none())
+            .map(|r| r.benchmark.as_str())
+            .collect();
+        if !missing.is_empty() {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "**Notes:**");
+            for name in missing {
+                let _ = writeln!(
+                    out,
+                    "- `{}`: {} or {} result missing from this run",
+                    name,
+                    display_language(subject),
+                    data.baseline_label()
+                );
+            }
+        }
+    }
+
+    let _ = writeln!(out);
+    let _ = writeln!(out, "---");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "*Generated by BMB Benchmark Suite*");
+    out
+}
+
+/// Replace every marked table in `readme` with a freshly rendered one.
+/// Returns the new text and the number of tables rewritten.
+pub fn update_marked_tables(readme: &str, data: &ReportData) -> Result<(String, usize), String> {
+    let mut out = String::new();
+    let mut rest = readme;
+    let mut count = 0;
+
+    while let Some(start) = rest.find(TABLE_START) {
+        let marker_end = start
+            + rest[start..]
+                .find("-->")
+                .ok_or("unterminated bench-table marker")?
+            + 3;
+        let category = rest[start + TABLE_START.len()..marker_end - 3].trim();
+        let end = marker_end
+            + rest[marker_end..]
+                .find(TABLE_END)
+                .ok_or_else(|| format!("missing {} for table '{}'", TABLE_END, category))?;
+
+        let table = if category == "all" {
+            let rows: Vec<&Row> = data.categories.values().flatten().collect();
+            markdown_table(data, &rows)
+        } else {
+            let rows = data
+                .categories
+                .get(category)
+                .ok_or_else(|| format!("category '{}' not in this run", category))?;
+            markdown_table(data, &rows.iter().collect::<Vec<_>>())
+        };
+
+        out.push_str(&rest[..marker_end]);
+        out.push('\n');
+        out.push_str(&table);
+        out.push_str(TABLE_END);
+        rest = &rest[end + TABLE_END.len()..];
+        count += 1;
+    }
+
+    out.push_str(rest);
+    Ok((out, count))
+}
+
+fn markdown_table<R: std::borrow::Borrow<Row>>(data: &ReportData, rows: &[R]) -> String {
+    let mut out = String::new();
+    let subject = data.subject_language();
+
+    let mut header = String::from("| Benchmark |");
+    let mut rule = String::from("|-----------|");
+    for lang in &data.languages {
+        let title = format!(" {} (ms) |", display_language(lang));
+        rule.push_str(&"-".repeat(title.len() - 1));
+        rule.push('|');
+        header.push_str(&title);
+    }
+    header.push_str(" Ratio | Status |");
+    rule.push_str("-------|--------|");
+    let _ = writeln!(out, "{}", header);
+    let _ = writeln!(out, "{}", rule);
+
+    for row in rows {
+        let row = row.borrow();
+        let _ = write!(out, "| {} |", row.benchmark);
+        for lang in &data.languages {
+            match row.cell(lang) {
+                Some(cell) => {
+                    let _ = write!(out, " {:.2} |", cell.median_ms);
+                }
+                None => out.push_str(" - |"),
+            }
+        }
+        match row.subject_ratio(subject) {
+            Some(ratio) => {
+                let _ = writeln!(out, " **{:.2}x** | {} |", ratio, status_text(subject, ratio));
+            }
+            None => {
+                let _ = writeln!(out, " N/A | ⚠️ Not measured |");
+            }
+        }
+    }
+
+    out
+}
+
+fn status_text(subject: &str, ratio: f64) -> String {
+    let name = display_language(subject);
+    if ratio < 1.0 {
+        format!("✅ {} {:.0}% faster", name, (1.0 - ratio) * 100.0)
+    } else if ratio <= PARITY_RATIO {
+        "✅ Parity".to_string()
+    } else if ratio <= SLOW_RATIO {
+        format!("⚠️ {} {:.0}% slower", name, (ratio - 1.0) * 100.0)
+    } else {
+        format!("❌ {} {:.1}x slower", name, ratio)
+    }
+}
+
+fn category_title(category: &str) -> String {
+    match category {
+        "compute" => "Compute-Intensive".to_string(),
+        "contract" => "Contract-Optimized".to_string(),
+        "real_world" => "Real-World".to_string(),
+        "bootstrap" => "Bootstrap".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn display_language(lang: &str) -> String {
+    match lang {
+        "rust" => "Rust".to_string(),
+        other => other.to_uppercase(),
+    }
+}
+
+const BAR_HEIGHT: usize = 14;
+const LABEL_WIDTH: usize = 180;
+const CHART_WIDTH: usize = 420;
+
+/// Horizontal bar chart of ratios against the baseline, with a 1.0x marker.
+fn ratio_chart(rows: &[Row], ratio_langs: &[&String]) -> String {
+    let bars: Vec<(String, f64)> = rows
+        .iter()
+        .flat_map(|row| {
+            ratio_langs.iter().filter_map(move |lang| {
+                let ratio = row.cell(lang).and_then(|c| c.ratio)?;
+                if &&row.baseline == lang {
+                    return None;
+                }
+                Some((format!("{} ({})", row.benchmark, lang.to_uppercase()), ratio))
+            })
+        })
+        .collect();
+
+    if bars.is_empty() {
+        return String::new();
+    }
+
+    let max_ratio = bars.iter().map(|(_, r)| *r).fold(1.0, f64::max) * 1.1;
+    let scale = CHART_WIDTH as f64 / max_ratio;
+    let height = bars.len() * (BAR_HEIGHT + 6) + 24;
+    let width = LABEL_WIDTH + CHART_WIDTH + 60;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-size=\"11\">\n",
+        width, height
+    );
+    for (i, (label, ratio)) in bars.iter().enumerate() {
+        let y = i * (BAR_HEIGHT + 6) + 4;
+        let bar_width = (ratio * scale).max(1.0);
+        let color = if *ratio <= 1.0 { "#2da44e" } else { "#cf222e" };
+        let _ = writeln!(
+            svg,
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>\
+             <rect x=\"{}\" y=\"{}\" width=\"{:.1}\" height=\"{}\" fill=\"{}\"/>\
+             <text x=\"{:.1}\" y=\"{}\">{:.2}x</text>",
+            LABEL_WIDTH - 6,
+            y + BAR_HEIGHT - 3,
+            escape(label),
+            LABEL_WIDTH,
+            y,
+            bar_width,
+            BAR_HEIGHT,
+            color,
+            LABEL_WIDTH as f64 + bar_width + 4.0,
+            y + BAR_HEIGHT - 3,
+            ratio
+        );
+    }
+
+    let baseline_x = LABEL_WIDTH as f64 + scale;
+    let axis_y = bars.len() * (BAR_HEIGHT + 6) + 4;
+    let _ = writeln!(
+        svg,
+        "<line x1=\"{0:.1}\" y1=\"0\" x2=\"{0:.1}\" y2=\"{1}\" stroke=\"#555\" stroke-dasharray=\"4 3\"/>\
+         <text x=\"{0:.1}\" y=\"{2}\" text-anchor=\"middle\">1.0x</text>",
+        baseline_x,
+        axis_y,
+        axis_y + 14
+    );
+    svg.push_str("</svg>\n");
+    svg
+}
+
+const SORT_SCRIPT: &str = r#"<script>
+document.querySelectorAll("table.sortable").forEach(function (table) {
+  table.querySelectorAll("th").forEach(function (th, col) {
+    th.addEventListener("click", function () {
+      var asc = !th.classList.contains("asc");
+      table.querySelectorAll("th").forEach(function (h) { h.classList.remove("asc", "desc"); });
+      th.classList.add(asc ? "asc" : "desc");
+      var body = table.tBodies[0];
+      var rows = Array.prototype.slice.call(body.rows);
+      rows.sort(function (a, b) {
+        var x = a.cells[col].dataset.value, y = b.cells[col].dataset.value;
+        var nx = parseFloat(x), ny = parseFloat(y);
+        var cmp = (isNaN(nx) || isNaN(ny))
+          ? a.cells[col].textContent.localeCompare(b.cells[col].textContent)
+          : nx - ny;
+        return asc ? cmp : -cmp;
+      });
+      rows.forEach(function (r) { body.appendChild(r); });
+    });
+  });
+});
+</script>
+"#;
+
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,203 @@
+//! Stored benchmark results.
+//!
+//! Every `run` and `gate` invocation is saved as one [`RunDocument`] in
+//! `results/<timestamp>_<kind>.json`. The schema is versioned: fields are
+//! only ever added (with serde defaults), and `schema_version` is bumped
+//! when an existing field changes meaning. Files from a newer schema than
+//! this runner understands are rejected rather than misread.
+
+use crate::host::{self, HostInfo};
+use crate::isolate::{self, Isolation};
+use crate::schedule::{self, Schedule};
+use crate::ComparisonReport;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Current result document schema.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// One saved invocation of the runner.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RunDocument {
+    pub schema_version: u32,
+    /// Command that produced the document: "run" or "gate"
+    pub kind: String,
+    /// Local time the invocation finished, RFC 3339
+    pub timestamp: String,
+    pub runner_version: String,
+    pub command_line: Vec<String>,
+    /// Free-form label such as "phase35", set with `--session`
+    #[serde(default)]
+    pub session: Option<String>,
+    /// `git describe` of the benchmark repository, "-dirty" if modified
+    #[serde(default)]
+    pub git_revision: Option<String>,
+    /// Tool name -> first line of its `--version` output
+    #[serde(default)]
+    pub toolchains: BTreeMap<String, String>,
+    #[serde(default)]
+    pub host: Option<HostInfo>,
+    /// CPU pinning, priority and ASLR settings of the measured processes
+    #[serde(default)]
+    pub isolation: Option<Isolation>,
+    /// Order in which samples were taken across implementations
+    #[serde(default)]
+    pub schedule: Option<Schedule>,
+    /// Preflight noise checks that warned before measuring
+    #[serde(default)]
+    pub preflight_warnings: Vec<String>,
+    pub reports: Vec<ComparisonReport>,
+    #[serde(default)]
+    pub gate: Option<GateRecord>,
+}
+
+/// Verdict of a `gate` invocation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GateRecord {
+    pub name: String,
+    pub passed: bool,
+    /// Neither passed nor failed within the sampling budget
+    #[serde(default)]
+    pub inconclusive: bool,
+    pub passed_count: usize,
+    pub failed_count: usize,
+    #[serde(default)]
+    pub inconclusive_count: usize,
+    pub excluded_count: usize,
+}
+
+impl RunDocument {
+    pub fn new(kind: &str, reports: Vec<ComparisonReport>) -> Self {
+        RunDocument {
+            schema_version: SCHEMA_VERSION,
+            kind: kind.to_string(),
+            timestamp: Local::now().to_rfc3339(),
+            runner_version: env!("CARGO_PKG_VERSION").to_string(),
+            command_line: std::env::args().collect(),
+            session: None,
+            git_revision: git_revision(),
+            toolchains: host::toolchain_versions(),
+            host: Some(HostInfo::detect()),
+            isolation: isolate::current().cloned(),
+            schedule: Some(schedule::current()),
+            preflight_warnings: Vec::new(),
+            reports,
+            gate: None,
+        }
+    }
+
+    /// Write the document to `dir` under a timestamped name. An existing
+    /// file is never replaced: a second save within the same second gets a
+    /// `-2`, `-3`, ... suffix.
+    pub fn save(&self, dir: &Path) -> Result<PathBuf, String> {
+        let stamp = Local::now().format("%Y-%m-%d_%H%M%S");
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+
+        let mut n = 1;
+        loop {
+            let suffix = if n == 1 { String::new() } else { format!("-{}", n) };
+            let path = dir.join(format!("{}_{}{}.json", stamp, self.kind, suffix));
+            match fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    file.write_all(json.as_bytes())
+                        .map_err(|e| format!("{}: {}", path.display(), e))?;
+                    return Ok(path);
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => n += 1,
+                Err(e) => return Err(format!("{}: {}", path.display(), e)),
+            }
+        }
+    }
+}
+
+/// The `results/` directory next to `benches/`.
+pub fn results_dir(benches_dir: &Path) -> PathBuf {
+    benches_dir
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join("results")
+}
+
+/// Prefixes of JSON files in results/ that are not runs.
+const NON_RUN_PREFIXES: [&str; 3] = ["report-", "calibration-", "gate-"];
+
+/// Most recently modified JSON result file in `dir`. Rendered reports and
+/// noise calibrations are not runs and are skipped.
+pub fn latest_run(dir: &Path) -> Option<PathBuf> {
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "json"))
+        .filter(|p| {
+            let name = p.file_name().unwrap_or_default().to_string_lossy();
+            !NON_RUN_PREFIXES.iter().any(|prefix| name.starts_with(prefix))
+        })
+        .max_by_key(|p| fs::metadata(p).and_then(|m| m.modified()).ok())
+}
+
+/// Read a result file.
+///
+/// Accepts a [`RunDocument`], or the bare `ComparisonReport` (or array of
+/// them) written by hand before documents were versioned.
+pub fn load_run(path: &Path) -> Result<RunDocument, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let value: serde_json::Value =
+        serde_json::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    if let Some(version) = value.get("schema_version").and_then(|v| v.as_u64()) {
+        if version > SCHEMA_VERSION as u64 {
+            return Err(format!(
+                "{}: schema version {} is newer than supported version {}",
+                path.display(),
+                version,
+                SCHEMA_VERSION
+            ));
+        }
+        return serde_json::from_value(value).map_err(|e| format!("{}: {}", path.display(), e));
+    }
+
+    let reports = serde_json::from_value::<Vec<ComparisonReport>>(value.clone())
+        .or_else(|_| serde_json::from_value::<ComparisonReport>(value).map(|r| vec![r]))
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    let timestamp = reports.iter().map(|r| r.timestamp.clone()).max().unwrap_or_default();
+    Ok(RunDocument {
+        schema_version: 0,
+        kind: "legacy".to_string(),
+        timestamp,
+        runner_version: String::new(),
+        command_line: Vec::new(),
+        session: None,
+        git_revision: None,
+        toolchains: BTreeMap::new(),
+        host: None,
+        isolation: None,
+        schedule: None,
+        preflight_warnings: Vec::new(),
+        reports,
+        gate: None,
+    })
+}
+
+/// Read only the benchmark reports of a result file.
+pub fn load_reports(path: &Path) -> Result<Vec<ComparisonReport>, String> {
+    load_run(path).map(|doc| doc.reports)
+}
+
+fn git_revision() -> Option<String> {
+    let output = Command::new("git")
+        .args(["describe", "--always", "--dirty"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
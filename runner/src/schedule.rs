@@ -0,0 +1,144 @@
+//! Execution order of timed samples across implementations.
+//!
+//! Running every iteration of one language before the next lets thermal
+//! drift and background activity bias whichever language runs later. By
+//! default samples are taken round-robin (C, Rust, BMB, C, Rust, BMB, ...);
+//! `--order shuffle` permutes every round with a seeded RNG, and
+//! `--order sequential` keeps the old blocked order for comparison.
+
+use crate::rng::Rng;
+use clap::Args;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+static CONFIG: OnceLock<Schedule> = OnceLock::new();
+static RNG: Mutex<Option<Rng>> = Mutex::new(None);
+
+/// Command-line options shared by `run`, `compare` and `gate`.
+#[derive(Args, Debug, Clone)]
+pub struct ScheduleArgs {
+    /// Sample order across implementations (round-robin, shuffle, sequential)
+    #[arg(long, default_value = "round-robin")]
+    pub order: String,
+
+    /// Seed for --order shuffle (defaults to the current time)
+    #[arg(long)]
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Order {
+    Sequential,
+    RoundRobin,
+    Shuffle,
+}
+
+/// Order used for a run, recorded with the results.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Schedule {
+    pub order: Order,
+    /// Seed of the shuffle; None for deterministic orders
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Schedule {
+            order: Order::RoundRobin,
+            seed: None,
+        }
+    }
+}
+
+/// Validate the options and make them apply to every later [`measure`].
+/// Exits with code 2 on an unknown order.
+pub fn init(args: &ScheduleArgs) {
+    let order = match args.order.as_str() {
+        "sequential" => Order::Sequential,
+        "round-robin" => Order::RoundRobin,
+        "shuffle" => Order::Shuffle,
+        other => {
+            println!(
+                "{} Unknown order: {}. Valid: round-robin, shuffle, sequential",
+                "Error:".red(),
+                other
+            );
+            std::process::exit(2);
+        }
+    };
+
+    let seed = (order == Order::Shuffle).then(|| args.seed.unwrap_or_else(Rng::time_seed));
+    if let Some(seed) = seed {
+        println!("{} shuffled, seed {}", "Schedule:".cyan(), seed);
+        *RNG.lock().unwrap() = Some(Rng::new(seed));
+    }
+    let _ = CONFIG.set(Schedule { order, seed });
+}
+
+/// Schedule in effect (round-robin unless configured).
+pub fn current() -> Schedule {
+    CONFIG.get().cloned().unwrap_or_default()
+}
+
+/// Time every executable `iterations` times after `warmup` untimed runs,
+/// in the configured order. Returns the samples of each executable in
+/// input order. An executable that fails once is reported and not run
+/// again, and has no samples.
+pub fn measure(executables: &[&Path], iterations: u32, warmup: u32) -> Vec<Vec<f64>> {
+    let mut times = vec![Vec::new(); executables.len()];
+    let mut failed = vec![false; executables.len()];
+    let order = current().order;
+
+    // Times a run, or marks the executable failed
+    let mut run = |i: usize| -> Option<f64> {
+        if failed[i] {
+            return None;
+        }
+        match crate::time_run(executables[i]) {
+            Ok(ms) => Some(ms),
+            Err(e) => {
+                println!("  {} {}: {}", "Failed:".red(), executables[i].display(), e);
+                failed[i] = true;
+                None
+            }
+        }
+    };
+
+    if order == Order::Sequential {
+        for (i, samples) in times.iter_mut().enumerate() {
+            for _ in 0..warmup {
+                let _ = run(i);
+            }
+            for _ in 0..iterations {
+                samples.extend(run(i));
+            }
+        }
+    } else {
+        for round in 0..warmup + iterations {
+            let mut indices: Vec<usize> = (0..executables.len()).collect();
+            if order == Order::Shuffle {
+                if let Some(rng) = RNG.lock().unwrap().as_mut() {
+                    rng.shuffle(&mut indices);
+                }
+            }
+
+            for i in indices {
+                let sample = run(i);
+                if round >= warmup {
+                    times[i].extend(sample);
+                }
+            }
+        }
+    }
+
+    for (samples, failed) in times.iter_mut().zip(failed) {
+        if failed {
+            samples.clear();
+        }
+    }
+    times
+}
@@ -0,0 +1,330 @@
+//! Benchmark selectors shared by `run`, `list`, `validate`, `compare`,
+//! `calibrate` and `report`.
+//!
+//! A selector is a comma-separated list of terms:
+//!
+//! ```text
+//! all                  every benchmark
+//! fibonacci            exact name (unknown names are an error)
+//! json_*  n_?ody       glob on the name
+//! /^(fa|ff)/           regular expression searched in the name
+//! tag:string           manifest tag or category
+//! suite:quick          named suite from suites.toml
+//! !term                exclude what term selects
+//! ```
+//!
+//! The result is the union of the included terms (everything if there are
+//! none) minus the excluded ones.
+
+use clap::Args;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const SUITES_FILE: &str = "suites.toml";
+
+/// Command-line options shared by the commands that pick benchmarks.
+#[derive(Args, Debug, Clone)]
+pub struct SelectArgs {
+    /// Benchmarks: names, globs, /regex/, tag:<tag>, suite:<name>, !<term> (comma-separated)
+    #[arg(default_value = "all", value_name = "SELECTOR")]
+    pub selector: String,
+
+    /// Category filter (compute, contract, real_world, bootstrap)
+    #[arg(short, long)]
+    pub category: Option<String>,
+
+    /// Leave out benchmarks matching these terms (repeatable, comma-separated)
+    #[arg(short = 'x', long, value_name = "SELECTOR")]
+    pub exclude: Vec<String>,
+}
+
+impl SelectArgs {
+    /// Selector text as given, for messages.
+    pub fn describe(&self) -> String {
+        let mut text = self.selector.clone();
+        for exclude in &self.exclude {
+            for term in exclude.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+                text.push_str(",!");
+                text.push_str(term);
+            }
+        }
+        text
+    }
+}
+
+#[derive(Debug)]
+enum Term {
+    All,
+    Name(String),
+    Pattern(Regex),
+    Tag(String),
+    Suite(Selector),
+}
+
+impl Term {
+    fn matches(&self, name: &str, tags: &[String]) -> bool {
+        match self {
+            Term::All => true,
+            Term::Name(n) => n == name,
+            Term::Pattern(re) => re.is_match(name),
+            Term::Tag(tag) => tags.contains(tag),
+            Term::Suite(selector) => selector.matches(name, tags),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Selector {
+    include: Vec<Term>,
+    exclude: Vec<Term>,
+}
+
+impl Selector {
+    /// Selector for the command-line options. Suites are read from
+    /// `suites.toml` next to the benches directory when one is referenced.
+    pub fn from_args(args: &SelectArgs, benches_dir: &Path) -> Result<Self, String> {
+        let mut suites = Suites::new(suites_path(benches_dir));
+        let mut selector = Selector::parse(&args.selector, &mut suites)?;
+        for exclude in &args.exclude {
+            let excluded = Selector::parse(exclude, &mut suites)?;
+            if !excluded.exclude.is_empty() {
+                return Err(format!("--exclude '{}' cannot itself exclude", exclude));
+            }
+            selector.exclude.extend(excluded.include);
+        }
+        Ok(selector)
+    }
+
+    fn parse(text: &str, suites: &mut Suites) -> Result<Self, String> {
+        let mut selector = Selector::default();
+        for raw in text.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+            let (excluded, term) = match raw.strip_prefix('!') {
+                Some(rest) => (true, rest.trim()),
+                None => (false, raw),
+            };
+            let term = parse_term(term, suites)?;
+            if excluded {
+                selector.exclude.push(term);
+            } else {
+                selector.include.push(term);
+            }
+        }
+        Ok(selector)
+    }
+
+    pub fn matches(&self, name: &str, tags: &[String]) -> bool {
+        let included = self.include.is_empty() || self.include.iter().any(|t| t.matches(name, tags));
+        included && !self.exclude.iter().any(|t| t.matches(name, tags))
+    }
+
+    /// Exact names used anywhere in the selector, including suites.
+    fn names(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        for term in self.include.iter().chain(&self.exclude) {
+            match term {
+                Term::Name(n) => names.push(n.as_str()),
+                Term::Suite(selector) => names.extend(selector.names()),
+                _ => {}
+            }
+        }
+        names
+    }
+
+    /// Fails on the first exact name that is not one of `known`, so a typo
+    /// is not silently an empty selection.
+    pub fn check_names(&self, known: &[&str]) -> Result<(), String> {
+        for name in self.names() {
+            if known.contains(&name) {
+                continue;
+            }
+            let hint = known
+                .iter()
+                .find(|k| k.replace('-', "_") == name.replace('-', "_"))
+                .map(|k| format!(" (did you mean '{}'?)", k))
+                .unwrap_or_default();
+            return Err(format!("unknown benchmark '{}'{}", name, hint));
+        }
+        Ok(())
+    }
+}
+
+fn parse_term(term: &str, suites: &mut Suites) -> Result<Term, String> {
+    if term == "all" {
+        return Ok(Term::All);
+    }
+    if let Some(tag) = term.strip_prefix("tag:") {
+        return Ok(Term::Tag(tag.to_string()));
+    }
+    if let Some(name) = term.strip_prefix("suite:") {
+        return suites.expand(name).map(Term::Suite);
+    }
+    if let Some(pattern) = term.strip_prefix('/').and_then(|t| t.strip_suffix('/')) {
+        return Regex::new(pattern)
+            .map(Term::Pattern)
+            .map_err(|e| format!("invalid pattern '{}': {}", term, e));
+    }
+    if term.contains(['*', '?']) {
+        let pattern = regex::escape(term).replace(r"\*", ".*").replace(r"\?", ".");
+        return Regex::new(&format!("^{}$", pattern))
+            .map(Term::Pattern)
+            .map_err(|e| format!("invalid glob '{}': {}", term, e));
+    }
+    Ok(Term::Name(term.to_string()))
+}
+
+/// Lazily loaded `suites.toml`: suite name to a list of selector terms.
+///
+/// ```toml
+/// quick = ["fibonacci", "mandelbrot", "tag:contract"]
+/// ci = ["suite:quick", "tag:real_world", "!brainfuck"]
+/// ```
+struct Suites {
+    path: PathBuf,
+    defs: Option<BTreeMap<String, Vec<String>>>,
+    /// Suites being expanded, to reject cycles
+    stack: Vec<String>,
+}
+
+impl Suites {
+    fn new(path: PathBuf) -> Self {
+        Suites {
+            path,
+            defs: None,
+            stack: Vec::new(),
+        }
+    }
+
+    fn expand(&mut self, name: &str) -> Result<Selector, String> {
+        let defs = match self.defs.take() {
+            Some(defs) => defs,
+            None => {
+                let text = fs::read_to_string(&self.path).map_err(|e| format!("{}: {}", self.path.display(), e))?;
+                toml::from_str(&text).map_err(|e| format!("{}: {}", self.path.display(), e))?
+            }
+        };
+        let terms = defs.get(name).cloned();
+        let known: Vec<String> = defs.keys().cloned().collect();
+        self.defs = Some(defs);
+
+        let Some(terms) = terms else {
+            return Err(format!("unknown suite '{}' (defined: {})", name, known.join(", ")));
+        };
+        if self.stack.iter().any(|s| s == name) {
+            return Err(format!("suite '{}' includes itself ({} -> {})", name, self.stack.join(" -> "), name));
+        }
+
+        let text = terms.join(",");
+        self.stack.push(name.to_string());
+        let selector = Selector::parse(&text, self);
+        self.stack.pop();
+        selector
+    }
+}
+
+pub fn suites_path(benches_dir: &Path) -> PathBuf {
+    benches_dir.parent().unwrap_or(Path::new(".")).join(SUITES_FILE)
+}
+
+/// Discovered benchmarks matching the command-line selection, in
+/// discovery order.
+pub fn select(benches_dir: &Path, args: &SelectArgs) -> Result<Vec<(String, PathBuf)>, String> {
+    let selector = Selector::from_args(args, benches_dir)?;
+    let discovered = crate::discover_benchmarks(benches_dir, args.category.as_deref());
+    let known: Vec<&str> = discovered.iter().map(|(n, _)| n.as_str()).collect();
+    selector.check_names(&known)?;
+
+    Ok(discovered
+        .iter()
+        .filter(|(name, path)| selector.matches(name, &crate::manifest::tags(path)))
+        .cloned()
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn suites(defs: &str) -> Suites {
+        let mut suites = Suites::new(PathBuf::from(SUITES_FILE));
+        suites.defs = Some(toml::from_str(defs).unwrap());
+        suites
+    }
+
+    fn parse(text: &str) -> Selector {
+        Selector::parse(text, &mut suites("")).unwrap()
+    }
+
+    fn tags(list: &[&str]) -> Vec<String> {
+        list.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn empty_and_all_select_everything() {
+        assert!(parse("").matches("fibonacci", &[]));
+        assert!(parse("all").matches("fibonacci", &[]));
+    }
+
+    #[test]
+    fn names_globs_and_patterns() {
+        let selector = parse("fibonacci, json_*, n_?ody, /^(fa|ff)/");
+        for name in ["fibonacci", "json_parse", "n_body", "fasta", "fannkuch"] {
+            assert!(selector.matches(name, &[]), "{} not selected", name);
+        }
+        for name in ["fibonacci2", "http_parse", "mandelbrot"] {
+            assert!(!selector.matches(name, &[]), "{} selected", name);
+        }
+    }
+
+    #[test]
+    fn globs_are_anchored_and_escape_regex_characters() {
+        let selector = parse("a.c*");
+        assert!(selector.matches("a.cde", &[]));
+        assert!(!selector.matches("abcde", &[]));
+        assert!(!selector.matches("xa.c", &[]));
+    }
+
+    #[test]
+    fn exclusions_apply_after_inclusions() {
+        let selector = parse("tag:compute, !fibonacci, !/^n_/");
+        let compute = tags(&["compute"]);
+        assert!(selector.matches("mandelbrot", &compute));
+        assert!(!selector.matches("fibonacci", &compute));
+        assert!(!selector.matches("n_body", &compute));
+        assert!(!selector.matches("json_parse", &tags(&["real_world"])));
+
+        // Only exclusions: everything else
+        assert!(parse("!fibonacci").matches("mandelbrot", &[]));
+    }
+
+    #[test]
+    fn suites_expand_recursively_and_reject_cycles() {
+        let defs = r#"
+            quick = ["fibonacci", "tag:contract"]
+            ci = ["suite:quick", "mandelbrot", "!null_check"]
+            loop = ["suite:loop"]
+        "#;
+        let selector = Selector::parse("suite:ci", &mut suites(defs)).unwrap();
+        assert!(selector.matches("fibonacci", &[]));
+        assert!(selector.matches("mandelbrot", &[]));
+        assert!(selector.matches("aliasing", &tags(&["contract"])));
+        assert!(!selector.matches("null_check", &tags(&["contract"])));
+        assert_eq!(selector.names(), vec!["fibonacci", "mandelbrot", "null_check"]);
+
+        assert!(Selector::parse("suite:loop", &mut suites(defs)).unwrap_err().contains("includes itself"));
+        assert!(Selector::parse("suite:nightly", &mut suites(defs)).unwrap_err().contains("unknown suite"));
+    }
+
+    #[test]
+    fn invalid_patterns_and_unknown_names_are_errors() {
+        assert!(Selector::parse("/(/", &mut suites("")).is_err());
+
+        let selector = parse("fibonacci, reverse_complement");
+        assert_eq!(
+            selector.check_names(&["fibonacci", "reverse-complement"]),
+            Err("unknown benchmark 'reverse_complement' (did you mean 'reverse-complement'?)".to_string())
+        );
+        assert_eq!(parse("fib*").check_names(&["fibonacci"]), Ok(()));
+    }
+}
@@ -0,0 +1,151 @@
+//! Summary statistics for timing samples.
+
+use crate::rng::Rng;
+
+/// Bootstrap resamples used for confidence intervals.
+const BOOTSTRAP_RESAMPLES: usize = 2000;
+
+/// Fixed seed so the same samples always give the same interval.
+const BOOTSTRAP_SEED: u64 = 0x00b0_07c1;
+
+/// Point estimate with a confidence interval.
+#[derive(Debug, Clone, Copy)]
+pub struct Interval {
+    pub estimate: f64,
+    pub low: f64,
+    pub high: f64,
+}
+
+impl Interval {
+    /// True if the whole interval lies on one side of `value`.
+    pub fn excludes(&self, value: f64) -> bool {
+        self.high < value || self.low > value
+    }
+}
+
+pub fn median(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
+/// Linear-interpolated percentile, `p` in 0..=1.
+pub fn percentile(values: &[f64], p: f64) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    percentile_sorted(&sorted, p)
+}
+
+/// Linear-interpolated percentile of already sorted values, `p` in 0..=1.
+fn percentile_sorted(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = p.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+/// `--confidence` value: a level strictly between 0 and 1.
+pub fn parse_confidence(text: &str) -> Result<f64, String> {
+    let level: f64 = text.parse().map_err(|_| format!("'{}' is not a number", text))?;
+    if level > 0.0 && level < 1.0 {
+        Ok(level)
+    } else {
+        Err(format!("{} is not between 0 and 1 (e.g. 0.95)", level))
+    }
+}
+
+/// Ratio of medians `numer / denom` with a percentile bootstrap
+/// confidence interval at the given level (e.g. 0.95).
+pub fn ratio_ci(numer: &[f64], denom: &[f64], confidence: f64) -> Interval {
+    let estimate = median(numer) / median(denom);
+    if numer.len() < 2 || denom.len() < 2 {
+        return Interval { estimate, low: estimate, high: estimate };
+    }
+
+    let mut rng = Rng::new(BOOTSTRAP_SEED);
+    let mut numer_sample = vec![0.0; numer.len()];
+    let mut denom_sample = vec![0.0; denom.len()];
+    let mut ratios = Vec::with_capacity(BOOTSTRAP_RESAMPLES);
+
+    for _ in 0..BOOTSTRAP_RESAMPLES {
+        for slot in numer_sample.iter_mut() {
+            *slot = numer[rng.below(numer.len())];
+        }
+        for slot in denom_sample.iter_mut() {
+            *slot = denom[rng.below(denom.len())];
+        }
+        ratios.push(median(&numer_sample) / median(&denom_sample));
+    }
+
+    ratios.sort_by(f64::total_cmp);
+    let tail = (1.0 - confidence) / 2.0;
+    Interval {
+        estimate,
+        low: percentile_sorted(&ratios, tail),
+        high: percentile_sorted(&ratios, 1.0 - tail),
+    }
+}
+
+/// Geometric mean of positive values (ratios); 1.0 for an empty slice.
+pub fn geomean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 1.0;
+    }
+    (values.iter().map(|v| v.ln()).sum::<f64>() / values.len() as f64).exp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ratio_ci_brackets_the_ratio_of_medians() {
+        let numer = [10.0, 11.0, 12.0, 13.0, 14.0];
+        let denom = [9.0, 10.0, 10.0, 10.0, 11.0];
+        let ci = ratio_ci(&numer, &denom, 0.95);
+        assert_eq!(ci.estimate, 1.2);
+        assert!(ci.low <= ci.estimate && ci.estimate <= ci.high);
+        assert!(ci.low < ci.high);
+    }
+
+    #[test]
+    fn ratio_ci_is_reproducible_and_widens_with_confidence() {
+        let numer = [10.0, 12.0, 11.0, 15.0, 9.0, 13.0];
+        let denom = [10.0, 10.5, 9.5, 11.0, 10.2, 9.8];
+        let narrow = ratio_ci(&numer, &denom, 0.5);
+        let wide = ratio_ci(&numer, &denom, 0.99);
+        let again = ratio_ci(&numer, &denom, 0.5);
+        assert_eq!((narrow.low, narrow.high), (again.low, again.high));
+        assert!(wide.low <= narrow.low && narrow.high <= wide.high);
+    }
+
+    #[test]
+    fn ratio_ci_without_spread_is_a_point() {
+        let ci = ratio_ci(&[20.0, 20.0, 20.0], &[10.0, 10.0, 10.0], 0.95);
+        assert_eq!((ci.low, ci.estimate, ci.high), (2.0, 2.0, 2.0));
+
+        let single = ratio_ci(&[30.0], &[10.0, 12.0], 0.95);
+        assert_eq!((single.low, single.high), (single.estimate, single.estimate));
+    }
+
+    #[test]
+    fn confidence_must_be_strictly_between_0_and_1() {
+        assert_eq!(parse_confidence("0.95"), Ok(0.95));
+        for text in ["0", "1", "95", "-0.5", "NaN", "high"] {
+            assert!(parse_confidence(text).is_err(), "{} accepted", text);
+        }
+    }
+}
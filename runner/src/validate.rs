@@ -0,0 +1,179 @@
+//! Output validation for benchmark implementations.
+//!
+//! Implementations print their answers in slightly different formats
+//! (`fibonacci(35) = 9227465` vs `9227465`), so outputs are compared on
+//! every number they print, except the arguments of a leading
+//! `name(args) =` or `name(args):` label. Lines reporting timings or
+//! throughput are dropped before comparison since they differ between
+//! every run.
+
+use std::fs;
+use std::path::Path;
+use std::process::{Command, ExitStatus};
+
+/// File in a benchmark directory that pins the expected output.
+/// When present it is the reference for every language; otherwise the
+/// C implementation is.
+pub const EXPECTED_OUTPUT_FILE: &str = "expected_output.txt";
+
+/// Words that mark a line as timing information rather than a result.
+const TIMING_MARKERS: &[&str] = &["time:", "throughput", "elapsed", "seconds"];
+
+/// Normalized output of one implementation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Answer {
+    pub numbers: Vec<String>,
+}
+
+/// Run an executable once and normalize its stdout.
+pub fn capture(executable: &Path) -> Result<Answer, String> {
+    let output = Command::new(executable)
+        .output()
+        .map_err(|e| format!("failed to run: {}", e))?;
+
+    completed(output.status)?;
+    Ok(normalize(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Whether a finished program ran to completion. BMB programs return
+/// `main`'s value as their exit code, so any exit code counts; only a
+/// program terminated by a signal has no result.
+pub fn completed(status: ExitStatus) -> Result<(), String> {
+    match status.code() {
+        Some(_) => Ok(()),
+        None => Err("terminated by signal".to_string()),
+    }
+}
+
+/// Read the pinned expected output for a benchmark, if any.
+pub fn expected(bench_path: &Path) -> Option<Answer> {
+    fs::read_to_string(bench_path.join(EXPECTED_OUTPUT_FILE))
+        .ok()
+        .map(|text| normalize(&text))
+}
+
+/// Compare a candidate answer with the reference answer.
+pub fn compare(reference: &Answer, candidate: &Answer) -> Result<(), String> {
+    if reference == candidate {
+        return Ok(());
+    }
+
+    let position = reference
+        .numbers
+        .iter()
+        .zip(&candidate.numbers)
+        .position(|(a, b)| a != b)
+        .unwrap_or_else(|| reference.numbers.len().min(candidate.numbers.len()));
+
+    Err(format!(
+        "output mismatch at value #{}: expected {}, got {}",
+        position + 1,
+        reference.numbers.get(position).map(String::as_str).unwrap_or("<end>"),
+        candidate.numbers.get(position).map(String::as_str).unwrap_or("<end>"),
+    ))
+}
+
+/// Validate a candidate executable against a benchmark's reference.
+///
+/// The reference is the pinned expected output if the benchmark has one,
+/// otherwise the output of `reference_exe`.
+pub fn check(bench_path: &Path, reference_exe: &Path, candidate_exe: &Path) -> Result<(), String> {
+    let reference = capture(reference_exe).map_err(|e| format!("reference {}", e))?;
+    let candidate = capture(candidate_exe)?;
+
+    if let Some(expected) = expected(bench_path) {
+        compare(&expected, &reference).map_err(|e| format!("reference {}", e))?;
+        return compare(&expected, &candidate);
+    }
+
+    compare(&reference, &candidate)
+}
+
+fn normalize(text: &str) -> Answer {
+    let mut numbers = Vec::new();
+
+    for line in text.lines() {
+        let lower = line.to_lowercase();
+        if TIMING_MARKERS.iter().any(|marker| lower.contains(marker)) {
+            continue;
+        }
+        numbers.extend(line_values(line));
+    }
+
+    Answer { numbers }
+}
+
+/// Values of one output line; numbers in a leading label such as
+/// `fibonacci(35) =` are arguments, not results.
+fn line_values(line: &str) -> Vec<String> {
+    numeric_tokens(strip_call_label(line))
+}
+
+/// `fibonacci(35) = 9227465` -> ` 9227465`; any other line is unchanged.
+fn strip_call_label(line: &str) -> &str {
+    let trimmed = line.trim_start();
+    let name_len = trimmed
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(trimmed.len());
+    let Some(args) = trimmed[name_len..].strip_prefix('(').filter(|_| name_len > 0) else {
+        return line;
+    };
+    let Some(close) = args.find(')') else {
+        return line;
+    };
+    args[close + 1..].trim_start().strip_prefix(['=', ':']).unwrap_or(line)
+}
+
+fn numeric_tokens(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+
+    for c in line.chars() {
+        let continues = c.is_ascii_digit()
+            || (c == '.' && !current.is_empty() && !current.contains('.'))
+            || (c == '-' && current.is_empty());
+        if continues {
+            current.push(c);
+        } else {
+            flush_token(&mut current, &mut tokens);
+        }
+    }
+    flush_token(&mut current, &mut tokens);
+
+    tokens
+}
+
+fn flush_token(current: &mut String, tokens: &mut Vec<String>) {
+    let token = current.trim_end_matches('.');
+    if token.chars().any(|c| c.is_ascii_digit()) {
+        tokens.push(token.to_string());
+    }
+    current.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labelled_and_unlabelled_answers_match() {
+        let labelled = normalize("fibonacci(35) = 9227465\n");
+        let unlabelled = normalize("9227465\n");
+        assert_eq!(labelled.numbers, vec!["9227465"]);
+        assert_eq!(compare(&labelled, &unlabelled), Ok(()));
+    }
+
+    #[test]
+    fn only_leading_call_labels_are_dropped() {
+        let answer = normalize("Pfannkuchen(10) = 38\nChecksum: 73196\nA 30.295\nTotal time: 1.5s\n");
+        assert_eq!(answer.numbers, vec!["38", "73196", "30.295"]);
+    }
+
+    #[test]
+    fn every_value_of_a_line_is_compared() {
+        assert_eq!(normalize("x=1 y=2 z=3").numbers, vec!["1", "2", "3"]);
+        assert_eq!(normalize("16 trees of depth 4 check: 496").numbers, vec!["16", "4", "496"]);
+        assert!(compare(&normalize("1 2 3"), &normalize("9 9 3")).is_err());
+        assert!(compare(&normalize("sum(3) = 1 2"), &normalize("2")).is_err());
+    }
+}